@@ -5,10 +5,325 @@
 pub use mappy_core::*;
 
 use anyhow::{Context, Result};
+use bytes::Bytes;
+use flate2::write::GzEncoder;
+use flate2::read::GzDecoder;
+use flate2::Compression;
+use h2::client::SendRequest;
 use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::io::{Read, Write};
 use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use tokio::net::UnixStream;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::mpsc;
+use tokio::sync::Mutex as AsyncMutex;
+use tokio_stream::wrappers::ReceiverStream;
+
+/// Content encodings the client negotiates for both outgoing `set` bodies and incoming
+/// responses. Advertised to the server as `Accept-Encoding: zstd, br, gzip` on every
+/// request; the same trio `stilts::benchmark::comparison` already benchmarks elsewhere in
+/// this workspace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Zstd,
+    Brotli,
+    Gzip,
+}
+
+impl Encoding {
+    fn token(&self) -> &'static str {
+        match self {
+            Encoding::Zstd => "zstd",
+            Encoding::Brotli => "br",
+            Encoding::Gzip => "gzip",
+        }
+    }
+
+    fn from_token(token: &str) -> Option<Self> {
+        match token.trim() {
+            "zstd" => Some(Encoding::Zstd),
+            "br" => Some(Encoding::Brotli),
+            "gzip" => Some(Encoding::Gzip),
+            _ => None,
+        }
+    }
+
+    fn compress(&self, body: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            Encoding::Zstd => Ok(zstd::stream::encode_all(body, 0)?),
+            Encoding::Brotli => {
+                let mut out = Vec::new();
+                let params = brotli::enc::BrotliEncoderParams::default();
+                brotli::BrotliCompress(&mut std::io::Cursor::new(body), &mut out, &params)?;
+                Ok(out)
+            }
+            Encoding::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(body)?;
+                Ok(encoder.finish()?)
+            }
+        }
+    }
+
+    fn decompress(&self, body: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            Encoding::Zstd => Ok(zstd::stream::decode_all(body)?),
+            Encoding::Brotli => {
+                let mut out = Vec::new();
+                brotli::BrotliDecompress(&mut std::io::Cursor::new(body), &mut out)?;
+                Ok(out)
+            }
+            Encoding::Gzip => {
+                let mut decoder = GzDecoder::new(body);
+                let mut out = Vec::new();
+                decoder.read_to_end(&mut out)?;
+                Ok(out)
+            }
+        }
+    }
+}
+
+/// Advertised on every request via the `Accept-Encoding` header, in the order the client
+/// prefers the server to pick from.
+const ACCEPT_ENCODING: &str = "zstd, br, gzip";
+
+/// Find a header's value by case-insensitive name in a raw `\r\n`-joined header block.
+fn find_header<'a>(headers: &'a str, name: &str) -> Option<&'a str> {
+    headers.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        key.trim().eq_ignore_ascii_case(name).then(|| value.trim())
+    })
+}
+
+fn find_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+fn parse_status_code(status_line: &str) -> Result<u16> {
+    status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| anyhow::anyhow!("malformed HTTP status line: {status_line:?}"))
+}
+
+/// Read a single HTTP/1.1 response from `stream`: parses the status line and headers up to
+/// the first blank line, then reads the body per `Transfer-Encoding: chunked` framing or
+/// `Content-Length`, rather than relying on the server closing the connection after every
+/// response. A non-2xx status is surfaced as an error instead of being handed to
+/// `serde_json::from_slice` as if it were a normal body.
+async fn read_http_response(stream: &mut UnixStream) -> Result<(Vec<u8>, Option<String>)> {
+    let mut buf = Vec::new();
+    let mut scratch = [0u8; 4096];
+
+    let header_end = loop {
+        if let Some(pos) = find_subslice(&buf, b"\r\n\r\n") {
+            break pos + 4;
+        }
+        let n = stream.read(&mut scratch).await?;
+        if n == 0 {
+            anyhow::bail!("connection closed before response headers completed");
+        }
+        buf.extend_from_slice(&scratch[..n]);
+    };
+
+    let headers_str = String::from_utf8_lossy(&buf[..header_end]).to_string();
+    let status_line = headers_str.split("\r\n").next().unwrap_or("");
+    let status_code = parse_status_code(status_line)?;
+
+    let content_encoding = find_header(&headers_str, "Content-Encoding").map(|s| s.to_string());
+    let is_chunked = find_header(&headers_str, "Transfer-Encoding")
+        .map(|v| v.eq_ignore_ascii_case("chunked"))
+        .unwrap_or(false);
+    let content_length = find_header(&headers_str, "Content-Length")
+        .and_then(|v| v.trim().parse::<usize>().ok());
+
+    let leftover = buf[header_end..].to_vec();
+    let body = if is_chunked {
+        read_chunked_body(stream, leftover).await?
+    } else if let Some(len) = content_length {
+        let mut body = leftover;
+        while body.len() < len {
+            let n = stream.read(&mut scratch).await?;
+            if n == 0 {
+                anyhow::bail!(
+                    "connection closed before full body received ({} of {} bytes)",
+                    body.len(),
+                    len
+                );
+            }
+            body.extend_from_slice(&scratch[..n]);
+        }
+        body.truncate(len);
+        body
+    } else {
+        // No framing information at all; fall back to reading until the connection
+        // closes, matching the previous (pre-keep-alive) behavior.
+        let mut body = leftover;
+        loop {
+            let n = stream.read(&mut scratch).await?;
+            if n == 0 {
+                break;
+            }
+            body.extend_from_slice(&scratch[..n]);
+        }
+        body
+    };
+
+    if !(200..300).contains(&status_code) {
+        anyhow::bail!(
+            "server returned HTTP {}: {}",
+            status_code,
+            String::from_utf8_lossy(&body)
+        );
+    }
+
+    Ok((body, content_encoding))
+}
+
+/// Decode a chunked-transfer body: `leftover` is whatever body bytes were already read
+/// past the header block; more are pulled from `stream` as needed. Each chunk is a hex
+/// size line, CRLF, that many data bytes, CRLF, terminated by a zero-length chunk.
+async fn read_chunked_body(stream: &mut UnixStream, leftover: Vec<u8>) -> Result<Vec<u8>> {
+    let mut buf = leftover;
+    let mut scratch = [0u8; 4096];
+    let mut body = Vec::new();
+    let mut pos = 0usize;
+
+    loop {
+        let line_end = loop {
+            if let Some(rel) = find_subslice(&buf[pos..], b"\r\n") {
+                break pos + rel;
+            }
+            let n = stream.read(&mut scratch).await?;
+            if n == 0 {
+                anyhow::bail!("connection closed mid chunk-size line");
+            }
+            buf.extend_from_slice(&scratch[..n]);
+        };
+
+        let size_line = std::str::from_utf8(&buf[pos..line_end])
+            .context("chunk size line is not valid UTF-8")?;
+        let size_token = size_line.split(';').next().unwrap_or("").trim();
+        let chunk_size = usize::from_str_radix(size_token, 16)
+            .with_context(|| format!("malformed chunk size: {size_line:?}"))?;
+
+        pos = line_end + 2;
+
+        if chunk_size == 0 {
+            // RFC 7230 §4.1: the terminal zero-size chunk is followed by a (possibly
+            // empty) trailer section, itself terminated by a blank line. Leaving this
+            // unread would strand it on the socket for the next pooled request to choke
+            // on as a stray leading CRLF before its status line.
+            loop {
+                let trailer_end = loop {
+                    if let Some(rel) = find_subslice(&buf[pos..], b"\r\n") {
+                        break pos + rel;
+                    }
+                    let n = stream.read(&mut scratch).await?;
+                    if n == 0 {
+                        anyhow::bail!("connection closed mid chunked trailer");
+                    }
+                    buf.extend_from_slice(&scratch[..n]);
+                };
+                let is_blank = trailer_end == pos;
+                pos = trailer_end + 2;
+                if is_blank {
+                    break;
+                }
+            }
+            break;
+        }
+
+        while buf.len() < pos + chunk_size + 2 {
+            let n = stream.read(&mut scratch).await?;
+            if n == 0 {
+                anyhow::bail!("connection closed mid chunk data");
+            }
+            buf.extend_from_slice(&scratch[..n]);
+        }
+
+        if &buf[pos + chunk_size..pos + chunk_size + 2] != b"\r\n" {
+            anyhow::bail!("malformed chunk: missing trailing CRLF after chunk data");
+        }
+        body.extend_from_slice(&buf[pos..pos + chunk_size]);
+        pos += chunk_size + 2;
+    }
+
+    Ok(body)
+}
+
+/// A pooled, idle Unix socket plus when it was returned to the pool, so `ConnectionPool`
+/// can discard connections that have sat idle past `idle_timeout`.
+struct PooledConnection {
+    stream: UnixStream,
+    idle_since: Instant,
+}
+
+/// Number of independent shards in a `ConnectionPool`; acquiring/releasing a connection
+/// for one endpoint only locks the shard its key hashes to, not the whole pool.
+const POOL_SHARD_COUNT: usize = 8;
+
+/// A sharded, bounded FIFO pool of idle Unix-socket connections, keyed by socket path. Reusing a
+/// live connection across calls avoids paying connect overhead on every `get`/`set`, which
+/// otherwise dominates latency for small maplet ops.
+struct ConnectionPool {
+    shards: Vec<Mutex<HashMap<String, VecDeque<PooledConnection>>>>,
+    max_idle_per_host: usize,
+    idle_timeout: Duration,
+}
+
+impl ConnectionPool {
+    fn new(max_idle_per_host: usize, idle_timeout: Duration) -> Self {
+        let shards = (0..POOL_SHARD_COUNT)
+            .map(|_| Mutex::new(HashMap::new()))
+            .collect();
+        Self {
+            shards,
+            max_idle_per_host,
+            idle_timeout,
+        }
+    }
+
+    fn shard_for(&self, endpoint: &str) -> &Mutex<HashMap<String, VecDeque<PooledConnection>>> {
+        let mut hasher = DefaultHasher::new();
+        endpoint.hash(&mut hasher);
+        let index = (hasher.finish() as usize) % self.shards.len();
+        &self.shards[index]
+    }
+
+    /// Take an idle, still-fresh connection for `endpoint`, if one is pooled. Connections
+    /// that have sat idle past `idle_timeout` are dropped rather than handed back, since a
+    /// peer may have already closed them.
+    fn acquire(&self, endpoint: &str) -> Option<UnixStream> {
+        let mut shard = self.shard_for(endpoint).lock().unwrap();
+        let queue = shard.get_mut(endpoint)?;
+        while let Some(conn) = queue.pop_front() {
+            if conn.idle_since.elapsed() < self.idle_timeout {
+                return Some(conn.stream);
+            }
+        }
+        None
+    }
+
+    /// Return a still-healthy connection to the pool for reuse. Dropped instead if the
+    /// per-endpoint idle limit is already full.
+    fn release(&self, endpoint: &str, stream: UnixStream) {
+        let mut shard = self.shard_for(endpoint).lock().unwrap();
+        let queue = shard.entry(endpoint.to_string()).or_default();
+        if queue.len() < self.max_idle_per_host {
+            queue.push_back(PooledConnection {
+                stream,
+                idle_since: Instant::now(),
+            });
+        }
+    }
+}
 
 /// Re-export commonly used types for convenience
 pub mod prelude {
@@ -26,13 +341,41 @@ pub struct ClientConfig {
     pub socket_path: Option<String>,
     /// HTTP URL (e.g., http://localhost:8003)
     pub http_url: Option<String>,
+    /// Codec and minimum body size (bytes) for compressing outgoing `set` bodies; bodies
+    /// smaller than the threshold are sent uncompressed, mirroring how gRPC skips tiny
+    /// frames. `None` disables outgoing compression. Incoming responses are always
+    /// decoded transparently regardless of this setting.
+    pub compression: Option<(Encoding, usize)>,
+    /// Keep Unix-socket/HTTP connections open and reuse them across calls instead of
+    /// opening a fresh one per request.
+    pub keep_alive: bool,
+    /// Maximum idle connections retained per endpoint (socket path, or HTTP host) when
+    /// `keep_alive` is enabled.
+    pub max_idle_per_host: usize,
+    /// How long an idle pooled connection may sit before it's discarded instead of reused.
+    pub idle_timeout: Duration,
+    /// Speak HTTP/2 over cleartext (h2c, prior-knowledge mode) on the Unix socket instead
+    /// of the hand-rolled HTTP/1.1 framing, multiplexing concurrent requests as independent
+    /// streams on one long-lived connection rather than serializing them. Ignored for the
+    /// HTTP transport, where `reqwest` already negotiates HTTP/2 on its own.
+    pub h2c: bool,
 }
 
+/// Bodies smaller than this are sent uncompressed by default.
+const DEFAULT_MIN_BODY_SIZE: usize = 1024;
+const DEFAULT_MAX_IDLE_PER_HOST: usize = 4;
+const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
 impl Default for ClientConfig {
     fn default() -> Self {
         Self {
             socket_path: Some("/var/run/reynard/mappy.sock".to_string()),
             http_url: None,
+            compression: Some((Encoding::Zstd, DEFAULT_MIN_BODY_SIZE)),
+            keep_alive: true,
+            max_idle_per_host: DEFAULT_MAX_IDLE_PER_HOST,
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
+            h2c: false,
         }
     }
 }
@@ -46,33 +389,89 @@ impl ClientConfig {
         let http_url = std::env::var("MAPPY_HTTP_URL")
             .ok()
             .filter(|s| !s.is_empty());
-        
+
         Self {
             socket_path: socket_path.or_else(|| Some("/var/run/reynard/mappy.sock".to_string())),
             http_url,
+            ..Self::default()
         }
     }
-    
+
     /// Use Unix socket
     pub fn with_socket<P: AsRef<Path>>(path: P) -> Self {
         Self {
             socket_path: Some(path.as_ref().to_string_lossy().to_string()),
             http_url: None,
+            ..Self::default()
         }
     }
-    
+
     /// Use HTTP
     pub fn with_http(url: impl Into<String>) -> Self {
         Self {
             socket_path: None,
             http_url: Some(url.into()),
+            ..Self::default()
         }
     }
+
+    /// Compress outgoing `set` bodies with `encoding` once they reach `min_body_size`
+    /// bytes.
+    pub fn with_compression(mut self, encoding: Encoding, min_body_size: usize) -> Self {
+        self.compression = Some((encoding, min_body_size));
+        self
+    }
+
+    /// Disable outgoing body compression; responses are still decoded transparently.
+    pub fn without_compression(mut self) -> Self {
+        self.compression = None;
+        self
+    }
+
+    /// Configure connection reuse: whether to keep connections alive, how many idle
+    /// connections to retain per endpoint, and how long one may sit idle before it's
+    /// discarded instead of reused.
+    pub fn with_keep_alive(mut self, keep_alive: bool, max_idle_per_host: usize, idle_timeout: Duration) -> Self {
+        self.keep_alive = keep_alive;
+        self.max_idle_per_host = max_idle_per_host;
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    /// Speak h2c (prior-knowledge HTTP/2 over cleartext) on the Unix socket instead of
+    /// HTTP/1.1, so pipelined `get`/`set` calls multiplex as independent streams on one
+    /// connection rather than serializing behind the request/response pool.
+    pub fn with_h2c(mut self) -> Self {
+        self.h2c = true;
+        self
+    }
 }
 
 /// Network client for Mappy server
 pub struct Client {
     config: ClientConfig,
+    /// Sharded pool of idle Unix-socket connections, reused across calls when
+    /// `config.keep_alive` is set.
+    pool: ConnectionPool,
+    /// Shared `reqwest::Client`, built once per `Client` so its own internal connection
+    /// pool (configured from `config.max_idle_per_host`/`config.idle_timeout`) is reused
+    /// across `get`/`set` calls instead of reconnecting every time.
+    http_client: reqwest::Client,
+    /// The long-lived, multiplexed h2c connection handle, lazily established on first use
+    /// when `config.h2c` is set. `None` until the first h2c request connects.
+    h2_conn: AsyncMutex<Option<SendRequest<Bytes>>>,
+}
+
+fn build_http_client(config: &ClientConfig) -> reqwest::Client {
+    let mut builder = reqwest::Client::builder();
+    if config.keep_alive {
+        builder = builder
+            .pool_max_idle_per_host(config.max_idle_per_host)
+            .pool_idle_timeout(config.idle_timeout);
+    } else {
+        builder = builder.pool_max_idle_per_host(0);
+    }
+    builder.build().unwrap_or_default()
 }
 
 #[derive(Serialize)]
@@ -88,6 +487,33 @@ struct GetResponse {
     found: bool,
 }
 
+/// Mirrors the server's `BatchOp`, packing many `mset`/`mget` entries into a single `/batch`
+/// request body instead of one round trip per key.
+#[derive(Serialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+enum BatchOp {
+    Set { key: String, value: String },
+    Get { key: String },
+}
+
+/// Mirrors the server's `BatchResult`. Also used to decode each ndjson line the `/scan`
+/// endpoint streams back, since it emits one of these per requested key.
+#[derive(Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+enum BatchResult {
+    Set {
+        #[allow(dead_code)]
+        key: String,
+        #[allow(dead_code)]
+        ok: bool,
+    },
+    Get {
+        key: String,
+        value: Option<String>,
+        found: bool,
+    },
+}
+
 #[derive(Deserialize)]
 struct HealthResponse {
     status: String,
@@ -97,93 +523,255 @@ struct HealthResponse {
 impl Client {
     /// Create a new client with default configuration
     pub fn new() -> Self {
-        Self {
-            config: ClientConfig::default(),
-        }
+        Self::with_config(ClientConfig::default())
     }
-    
+
     /// Create a new client with custom configuration
     pub fn with_config(config: ClientConfig) -> Self {
-        Self { config }
+        let pool = ConnectionPool::new(config.max_idle_per_host, config.idle_timeout);
+        let http_client = build_http_client(&config);
+        Self {
+            config,
+            pool,
+            http_client,
+            h2_conn: AsyncMutex::new(None),
+        }
     }
-    
+
     /// Create a client from environment variables
     pub fn from_env() -> Self {
-        Self {
-            config: ClientConfig::from_env(),
-        }
+        Self::with_config(ClientConfig::from_env())
     }
     
-    /// Send a request via Unix socket
-    async fn request_unix(&self, method: &str, path: &str, body: Option<&[u8]>) -> Result<Vec<u8>> {
-        let socket_path = self.config.socket_path.as_ref()
-            .ok_or_else(|| anyhow::anyhow!("Unix socket path not configured"))?;
-        
-        let mut stream = UnixStream::connect(socket_path).await
-            .with_context(|| format!("Failed to connect to Unix socket: {}", socket_path))?;
-        
-        // Build HTTP request
+    /// Write the HTTP/1.1 request and read back its response over an already-open `stream`.
+    async fn send_and_read(
+        stream: &mut UnixStream,
+        method: &str,
+        path: &str,
+        body: Option<&[u8]>,
+        headers: &[(&str, String)],
+        keep_alive: bool,
+    ) -> Result<(Vec<u8>, Option<String>)> {
         let mut request = format!("{} {} HTTP/1.1\r\n", method, path);
         request.push_str("Host: localhost\r\n");
         request.push_str("Content-Type: application/json\r\n");
-        
+        request.push_str(if keep_alive {
+            "Connection: keep-alive\r\n"
+        } else {
+            "Connection: close\r\n"
+        });
+        for (name, value) in headers {
+            request.push_str(&format!("{}: {}\r\n", name, value));
+        }
+
         if let Some(body) = body {
             request.push_str(&format!("Content-Length: {}\r\n", body.len()));
         }
         request.push_str("\r\n");
-        
-        // Send request
+
         stream.write_all(request.as_bytes()).await?;
         if let Some(body) = body {
             stream.write_all(body).await?;
         }
         stream.flush().await?;
-        
-        // Read response
-        let mut response = Vec::new();
-        stream.read_to_end(&mut response).await?;
-        
-        // Parse HTTP response (simple parser)
-        let response_str = String::from_utf8_lossy(&response);
-        let body_start = response_str.find("\r\n\r\n")
-            .map(|i| i + 4)
-            .unwrap_or(0);
-        
-        Ok(response[body_start..].to_vec())
+
+        read_http_response(stream).await
     }
-    
-    /// Send a request via HTTP
-    async fn request_http(&self, method: &str, path: &str, body: Option<&[u8]>) -> Result<Vec<u8>> {
+
+    /// Send a request via Unix socket. `headers` are appended verbatim after the standard
+    /// `Host`/`Content-Type`/`Connection` headers. Returns the response body plus its
+    /// `Content-Encoding` header, if any, so the caller can decode it.
+    ///
+    /// When `config.keep_alive` is set, reuses a pooled connection for `socket_path` if one
+    /// is available; if sending the request or reading the response over a reused
+    /// connection fails (the peer may have already closed it), retries once on a fresh
+    /// connection before giving up. A connection that serves a request successfully is
+    /// returned to the pool for the next caller.
+    async fn request_unix(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<&[u8]>,
+        headers: &[(&str, String)],
+    ) -> Result<(Vec<u8>, Option<String>)> {
+        let socket_path = self.config.socket_path.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Unix socket path not configured"))?;
+        let keep_alive = self.config.keep_alive;
+
+        let pooled = if keep_alive { self.pool.acquire(socket_path) } else { None };
+        let reused = pooled.is_some();
+        let mut stream = match pooled {
+            Some(stream) => stream,
+            None => UnixStream::connect(socket_path).await
+                .with_context(|| format!("Failed to connect to Unix socket: {}", socket_path))?,
+        };
+
+        let mut result = Self::send_and_read(&mut stream, method, path, body, headers, keep_alive).await;
+        if result.is_err() && reused {
+            stream = UnixStream::connect(socket_path).await
+                .with_context(|| format!("Failed to connect to Unix socket: {}", socket_path))?;
+            result = Self::send_and_read(&mut stream, method, path, body, headers, keep_alive).await;
+        }
+
+        if keep_alive && result.is_ok() {
+            self.pool.release(socket_path, stream);
+        }
+
+        result
+    }
+
+    /// Get a ready-to-use h2c `SendRequest` handle, establishing the connection (and
+    /// spawning the task that drives it) on first use or if the previously established
+    /// connection is no longer ready to accept a new stream.
+    async fn h2_send_request(&self) -> Result<SendRequest<Bytes>> {
+        let mut guard = self.h2_conn.lock().await;
+        if let Some(existing) = guard.clone() {
+            let mut candidate = existing;
+            if candidate.ready().await.is_ok() {
+                return Ok(candidate);
+            }
+        }
+
+        let socket_path = self.config.socket_path.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Unix socket path not configured"))?;
+        let stream = UnixStream::connect(socket_path).await
+            .with_context(|| format!("Failed to connect to Unix socket: {}", socket_path))?;
+        let (send_request, connection) = h2::client::handshake(stream).await
+            .context("h2c handshake failed")?;
+
+        // Drive the connection in the background for its lifetime; a failure here just
+        // means the next caller's `ready()` check fails and a fresh connection is opened.
+        tokio::spawn(async move {
+            let _ = connection.await;
+        });
+
+        *guard = Some(send_request.clone());
+        Ok(send_request)
+    }
+
+    /// Send a request as an independent stream on the shared h2c connection, multiplexing
+    /// concurrent `get`/`set` calls rather than serializing one request per socket the way
+    /// `request_unix` does. See `request_unix` for the `headers`/return contract.
+    async fn request_h2c(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<&[u8]>,
+        headers: &[(&str, String)],
+    ) -> Result<(Vec<u8>, Option<String>)> {
+        let mut send_request = self.h2_send_request().await?;
+
+        let mut builder = http::Request::builder()
+            .method(method)
+            .uri(path)
+            .header("host", "localhost");
+        for (name, value) in headers {
+            builder = builder.header(*name, value.as_str());
+        }
+        let request = builder.body(()).context("failed to build h2c request")?;
+
+        let (response_fut, mut send_stream) = send_request
+            .send_request(request, body.is_none())
+            .context("failed to open h2c stream")?;
+        if let Some(body) = body {
+            send_stream
+                .send_data(Bytes::copy_from_slice(body), true)
+                .context("failed to send h2c request body")?;
+        }
+
+        let response = response_fut.await.context("h2c request failed")?;
+        let status = response.status();
+        let content_encoding = response
+            .headers()
+            .get(http::header::CONTENT_ENCODING)
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string());
+
+        let mut recv_stream = response.into_body();
+        let mut body_bytes = Vec::new();
+        while let Some(chunk) = recv_stream.data().await {
+            let chunk = chunk.context("error reading h2c response body")?;
+            recv_stream
+                .flow_control()
+                .release_capacity(chunk.len())
+                .context("failed to release h2c flow-control capacity")?;
+            body_bytes.extend_from_slice(&chunk);
+        }
+
+        if !status.is_success() {
+            anyhow::bail!(
+                "server returned HTTP {}: {}",
+                status.as_u16(),
+                String::from_utf8_lossy(&body_bytes)
+            );
+        }
+
+        Ok((body_bytes, content_encoding))
+    }
+
+    /// Send a request via HTTP. See `request_unix` for the `headers`/return contract.
+    async fn request_http(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<&[u8]>,
+        headers: &[(&str, String)],
+    ) -> Result<(Vec<u8>, Option<String>)> {
         let url = self.config.http_url.as_ref()
             .ok_or_else(|| anyhow::anyhow!("HTTP URL not configured"))?;
-        
-        let client = reqwest::Client::new();
+
         let url = format!("{}{}", url.trim_end_matches('/'), path);
-        
-        let response = match method {
-            "GET" => client.get(&url).send().await?,
-            "POST" => {
-                let mut req = client.post(&url);
-                if let Some(body) = body {
-                    req = req.body(body.to_vec());
-                }
-                req.send().await?
-            }
+
+        let mut builder = match method {
+            "GET" => self.http_client.get(&url),
+            "POST" => self.http_client.post(&url),
             _ => return Err(anyhow::anyhow!("Unsupported method: {}", method)),
         };
-        
+        for (name, value) in headers {
+            builder = builder.header(*name, value.as_str());
+        }
+        if let Some(body) = body {
+            builder = builder.body(body.to_vec());
+        }
+
+        let response = builder.send().await?;
+        let content_encoding = response
+            .headers()
+            .get(reqwest::header::CONTENT_ENCODING)
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string());
         let bytes = response.bytes().await?;
-        Ok(bytes.to_vec())
+        Ok((bytes.to_vec(), content_encoding))
     }
-    
-    /// Send a request (auto-detects socket vs HTTP)
-    async fn request(&self, method: &str, path: &str, body: Option<&[u8]>) -> Result<Vec<u8>> {
-        if self.config.socket_path.is_some() {
-            self.request_unix(method, path, body).await
+
+    /// Send a request (auto-detects socket vs HTTP), negotiating content encoding: always
+    /// advertises `Accept-Encoding`, transparently decodes a compressed response, and
+    /// compresses `body` first if it's at or above the configured `min_body_size`.
+    async fn request(&self, method: &str, path: &str, body: Option<Vec<u8>>) -> Result<Vec<u8>> {
+        let mut headers = vec![("Accept-Encoding", ACCEPT_ENCODING.to_string())];
+
+        let body = match (&body, self.config.compression) {
+            (Some(raw), Some((encoding, min_body_size))) if raw.len() >= min_body_size => {
+                let compressed = encoding.compress(raw)?;
+                headers.push(("Content-Encoding", encoding.token().to_string()));
+                Some(compressed)
+            }
+            _ => body,
+        };
+
+        let (response_body, content_encoding) = if self.config.h2c && self.config.socket_path.is_some() {
+            self.request_h2c(method, path, body.as_deref(), &headers).await?
+        } else if self.config.socket_path.is_some() {
+            self.request_unix(method, path, body.as_deref(), &headers).await?
         } else if self.config.http_url.is_some() {
-            self.request_http(method, path, body).await
+            self.request_http(method, path, body.as_deref(), &headers).await?
         } else {
-            Err(anyhow::anyhow!("No connection method configured"))
+            return Err(anyhow::anyhow!("No connection method configured"));
+        };
+
+        match content_encoding.as_deref().and_then(Encoding::from_token) {
+            Some(encoding) => encoding.decompress(&response_body),
+            None => Ok(response_body),
         }
     }
     
@@ -201,10 +789,10 @@ impl Client {
             value: value.into(),
         };
         let body = serde_json::to_vec(&request)?;
-        self.request("POST", "/set", Some(&body)).await?;
+        self.request("POST", "/set", Some(body)).await?;
         Ok(())
     }
-    
+
     /// Get a value by key
     pub async fn get(&self, key: impl Into<String>) -> Result<Option<String>> {
         let key = key.into();
@@ -213,6 +801,238 @@ impl Client {
         let get_response: GetResponse = serde_json::from_slice(&response)?;
         Ok(get_response.value)
     }
+
+    /// Set many key-value pairs in a single `/batch` round trip instead of one `set` call
+    /// per entry.
+    pub async fn mset(&self, entries: impl IntoIterator<Item = (String, String)>) -> Result<()> {
+        let ops: Vec<BatchOp> = entries
+            .into_iter()
+            .map(|(key, value)| BatchOp::Set { key, value })
+            .collect();
+        let body = serde_json::to_vec(&ops)?;
+        self.request("POST", "/batch", Some(body)).await?;
+        Ok(())
+    }
+
+    /// Get many keys in a single `/batch` round trip instead of one `get` call per key.
+    /// Keys the server didn't find are present in the result map with a `None` value.
+    pub async fn mget(&self, keys: impl IntoIterator<Item = String>) -> Result<HashMap<String, Option<String>>> {
+        let ops: Vec<BatchOp> = keys.into_iter().map(|key| BatchOp::Get { key }).collect();
+        let body = serde_json::to_vec(&ops)?;
+        let response = self.request("POST", "/batch", Some(body)).await?;
+        let results: Vec<BatchResult> = serde_json::from_slice(&response)?;
+        Ok(results
+            .into_iter()
+            .filter_map(|result| match result {
+                BatchResult::Get { key, value, .. } => Some((key, value)),
+                BatchResult::Set { .. } => None,
+            })
+            .collect())
+    }
+
+    /// Stream `(key, value)` pairs for `keys` from the server's `/scan` endpoint, decoding
+    /// each newline-delimited JSON frame off the socket as it arrives rather than buffering
+    /// the whole response the way `mget` does — large scans never need the full result set
+    /// materialized in memory at once. Mappy's maplet is an approximate structure with no
+    /// key enumeration, so `keys` is the explicit keyspace to dump, not "every key stored".
+    ///
+    /// Only the Unix-socket transport is supported; this bypasses `request`/`Client`'s
+    /// pooling since the connection must stay open for the lifetime of the stream.
+    pub async fn scan(
+        &self,
+        keys: impl IntoIterator<Item = String>,
+    ) -> Result<ReceiverStream<Result<(String, Option<String>)>>> {
+        let socket_path = self
+            .config
+            .socket_path
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("scan requires a Unix socket-configured client"))?;
+        let keys: Vec<String> = keys.into_iter().collect();
+        let body = serde_json::to_vec(&keys)?;
+
+        let mut stream = UnixStream::connect(&socket_path)
+            .await
+            .with_context(|| format!("Failed to connect to Unix socket: {}", socket_path))?;
+
+        let mut request = String::from("POST /scan HTTP/1.1\r\n");
+        request.push_str("Host: localhost\r\n");
+        request.push_str("Content-Type: application/json\r\n");
+        request.push_str("Connection: close\r\n");
+        request.push_str(&format!("Content-Length: {}\r\n\r\n", body.len()));
+        stream.write_all(request.as_bytes()).await?;
+        stream.write_all(&body).await?;
+        stream.flush().await?;
+
+        let (tx, rx) = mpsc::channel(32);
+        tokio::spawn(async move {
+            if let Err(e) = stream_scan_rows(stream, &tx).await {
+                let _ = tx.send(Err(e)).await;
+            }
+        });
+
+        Ok(ReceiverStream::new(rx))
+    }
+}
+
+/// Incrementally decodes `Transfer-Encoding: chunked` frames off a stream one chunk at a
+/// time, so a caller can act on each chunk's data as it arrives instead of waiting for the
+/// terminal zero-size chunk the way `read_chunked_body` does for a response read as a whole.
+struct ChunkedReader {
+    buf: Vec<u8>,
+}
+
+impl ChunkedReader {
+    fn new(leftover: Vec<u8>) -> Self {
+        Self { buf: leftover }
+    }
+
+    /// Read and return the next chunk's data, pulling more bytes from `stream` as needed.
+    /// `Ok(None)` means the terminal zero-size chunk was seen; the body is complete.
+    async fn next_chunk(&mut self, stream: &mut UnixStream) -> Result<Option<Vec<u8>>> {
+        let mut scratch = [0u8; 4096];
+
+        let line_end = loop {
+            if let Some(pos) = find_subslice(&self.buf, b"\r\n") {
+                break pos;
+            }
+            let n = stream.read(&mut scratch).await?;
+            if n == 0 {
+                anyhow::bail!("connection closed mid chunk-size line");
+            }
+            self.buf.extend_from_slice(&scratch[..n]);
+        };
+
+        let size_line = std::str::from_utf8(&self.buf[..line_end])
+            .context("chunk size line is not valid UTF-8")?;
+        let size_token = size_line.split(';').next().unwrap_or("").trim();
+        let chunk_size = usize::from_str_radix(size_token, 16)
+            .with_context(|| format!("malformed chunk size: {size_line:?}"))?;
+
+        self.buf.drain(..line_end + 2);
+
+        if chunk_size == 0 {
+            // See `read_chunked_body`'s matching comment: the terminal chunk's trailer
+            // section must be consumed here too, or its bytes strand on the connection
+            // for whatever reuses it next.
+            loop {
+                let trailer_end = loop {
+                    if let Some(pos) = find_subslice(&self.buf, b"\r\n") {
+                        break pos;
+                    }
+                    let n = stream.read(&mut scratch).await?;
+                    if n == 0 {
+                        anyhow::bail!("connection closed mid chunked trailer");
+                    }
+                    self.buf.extend_from_slice(&scratch[..n]);
+                };
+                let is_blank = trailer_end == 0;
+                self.buf.drain(..trailer_end + 2);
+                if is_blank {
+                    break;
+                }
+            }
+            return Ok(None);
+        }
+
+        while self.buf.len() < chunk_size + 2 {
+            let n = stream.read(&mut scratch).await?;
+            if n == 0 {
+                anyhow::bail!("connection closed mid chunk data");
+            }
+            self.buf.extend_from_slice(&scratch[..n]);
+        }
+
+        if &self.buf[chunk_size..chunk_size + 2] != b"\r\n" {
+            anyhow::bail!("malformed chunk: missing trailing CRLF after chunk data");
+        }
+        let data = self.buf[..chunk_size].to_vec();
+        self.buf.drain(..chunk_size + 2);
+        Ok(Some(data))
+    }
+}
+
+/// Decode every complete `\n`-terminated line currently in `pending`, forwarding each row
+/// through `tx` as soon as it's available, leaving any trailing partial line in `pending`
+/// for the next round.
+async fn drain_scan_lines(
+    pending: &mut Vec<u8>,
+    tx: &mpsc::Sender<Result<(String, Option<String>)>>,
+) -> Result<bool> {
+    while let Some(pos) = find_subslice(pending, b"\n") {
+        let line: Vec<u8> = pending.drain(..=pos).collect();
+        let line = &line[..line.len() - 1];
+        if line.is_empty() {
+            continue;
+        }
+        let result: BatchResult =
+            serde_json::from_slice(line).context("malformed /scan response line")?;
+        let row = match result {
+            BatchResult::Get { key, value, .. } => (key, value),
+            BatchResult::Set { .. } => continue,
+        };
+        if tx.send(Ok(row)).await.is_err() {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Read the `/scan` response headers, then forward each decoded `(key, value)` row through
+/// `tx` as its line arrives, supporting both `Transfer-Encoding: chunked` (what axum's
+/// streaming body sends) and a `Content-Length`/EOF-terminated body.
+async fn stream_scan_rows(
+    mut stream: UnixStream,
+    tx: &mpsc::Sender<Result<(String, Option<String>)>>,
+) -> Result<()> {
+    let mut buf = Vec::new();
+    let mut scratch = [0u8; 4096];
+
+    let header_end = loop {
+        if let Some(pos) = find_subslice(&buf, b"\r\n\r\n") {
+            break pos + 4;
+        }
+        let n = stream.read(&mut scratch).await?;
+        if n == 0 {
+            anyhow::bail!("connection closed before scan response headers completed");
+        }
+        buf.extend_from_slice(&scratch[..n]);
+    };
+
+    let headers_str = String::from_utf8_lossy(&buf[..header_end]).to_string();
+    let status_line = headers_str.split("\r\n").next().unwrap_or("");
+    let status_code = parse_status_code(status_line)?;
+    if !(200..300).contains(&status_code) {
+        anyhow::bail!("server returned HTTP {} for /scan", status_code);
+    }
+    let is_chunked = find_header(&headers_str, "Transfer-Encoding")
+        .map(|v| v.eq_ignore_ascii_case("chunked"))
+        .unwrap_or(false);
+
+    let mut pending = buf[header_end..].to_vec();
+
+    if is_chunked {
+        let mut reader = ChunkedReader::new(std::mem::take(&mut pending));
+        while let Some(chunk) = reader.next_chunk(&mut stream).await? {
+            pending.extend_from_slice(&chunk);
+            if !drain_scan_lines(&mut pending, tx).await? {
+                return Ok(());
+            }
+        }
+    } else {
+        loop {
+            if !drain_scan_lines(&mut pending, tx).await? {
+                return Ok(());
+            }
+            let n = stream.read(&mut scratch).await?;
+            if n == 0 {
+                break;
+            }
+            pending.extend_from_slice(&scratch[..n]);
+        }
+    }
+
+    drain_scan_lines(&mut pending, tx).await?;
+    Ok(())
 }
 
 impl Default for Client {
@@ -224,12 +1044,159 @@ impl Default for Client {
 #[cfg(test)]
 mod tests {
     use super::prelude::*;
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration as StdDuration;
 
     #[tokio::test]
     async fn test_client_basic_usage() {
         let maplet = Maplet::<String, u64, CounterOperator>::new(100, 0.01).unwrap();
-        
+
         maplet.insert("test".to_string(), 42).await.unwrap();
         assert_eq!(maplet.query(&"test".to_string()).await, Some(42));
     }
+
+    /// Writes `data` into one end of an in-process Unix socket pair and hands back the
+    /// other end, so chunk-decoder tests can drive `read_chunked_body`/`ChunkedReader`
+    /// against a real `UnixStream` without a listening socket.
+    async fn piped_stream(data: &'static [u8]) -> UnixStream {
+        let (mut writer, reader) = UnixStream::pair().unwrap();
+        tokio::spawn(async move {
+            writer.write_all(data).await.unwrap();
+        });
+        reader
+    }
+
+    #[tokio::test]
+    async fn test_read_chunked_body_multi_chunk() {
+        let mut stream = piped_stream(b"5\r\nhello\r\n6\r\n world\r\n0\r\n\r\n").await;
+        let body = read_chunked_body(&mut stream, Vec::new()).await.unwrap();
+        assert_eq!(body, b"hello world");
+    }
+
+    #[tokio::test]
+    async fn test_read_chunked_body_consumes_trailer_headers() {
+        // A non-empty trailer section (here, one header line) must be consumed too, not
+        // just the bare terminal "0\r\n\r\n" case.
+        let mut stream =
+            piped_stream(b"5\r\nhello\r\n0\r\nX-Checksum: abc123\r\n\r\n").await;
+        let body = read_chunked_body(&mut stream, Vec::new()).await.unwrap();
+        assert_eq!(body, b"hello");
+    }
+
+    #[tokio::test]
+    async fn test_read_chunked_body_rejects_malformed_size() {
+        let mut stream = piped_stream(b"zz\r\nhello\r\n0\r\n\r\n").await;
+        let err = read_chunked_body(&mut stream, Vec::new()).await.unwrap_err();
+        assert!(err.to_string().contains("malformed chunk size"));
+    }
+
+    #[tokio::test]
+    async fn test_read_chunked_body_rejects_truncated_stream() {
+        let mut stream = piped_stream(b"5\r\nhel").await;
+        let err = read_chunked_body(&mut stream, Vec::new()).await.unwrap_err();
+        assert!(err.to_string().contains("connection closed"));
+    }
+
+    #[tokio::test]
+    async fn test_chunked_reader_multi_chunk() {
+        let mut stream = piped_stream(b"5\r\nhello\r\n6\r\n world\r\n0\r\n\r\n").await;
+        let mut reader = ChunkedReader::new(Vec::new());
+        let mut body = Vec::new();
+        while let Some(chunk) = reader.next_chunk(&mut stream).await.unwrap() {
+            body.extend_from_slice(&chunk);
+        }
+        assert_eq!(body, b"hello world");
+    }
+
+    #[tokio::test]
+    async fn test_chunked_reader_rejects_malformed_size() {
+        let mut stream = piped_stream(b"nope\r\nhello\r\n0\r\n\r\n").await;
+        let mut reader = ChunkedReader::new(Vec::new());
+        let err = reader.next_chunk(&mut stream).await.unwrap_err();
+        assert!(err.to_string().contains("malformed chunk size"));
+    }
+
+    #[tokio::test]
+    async fn test_chunked_reader_rejects_truncated_stream() {
+        let mut stream = piped_stream(b"5\r\nhel").await;
+        let mut reader = ChunkedReader::new(Vec::new());
+        let err = reader.next_chunk(&mut stream).await.unwrap_err();
+        assert!(err.to_string().contains("connection closed"));
+    }
+
+    static TEST_SOCKET_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+    /// A unique Unix-socket path under the system temp dir for one test's fake server,
+    /// since the repo has no `tempfile` dependency to lean on.
+    fn unique_socket_path() -> std::path::PathBuf {
+        let n = TEST_SOCKET_COUNTER.fetch_add(1, Ordering::Relaxed);
+        std::env::temp_dir().join(format!(
+            "mappy-client-test-{}-{}.sock",
+            std::process::id(),
+            n
+        ))
+    }
+
+    /// Regression test for the chunked-trailer bug: a keep-alive connection whose first
+    /// response is chunked must leave the socket positioned exactly at the next response's
+    /// status line, not a stray trailer CRLF, or the second `health()` call's status-line
+    /// parse fails.
+    #[tokio::test]
+    async fn test_keep_alive_connection_survives_chunked_response() {
+        let socket_path = unique_socket_path();
+        let listener = tokio::net::UnixListener::bind(&socket_path).unwrap();
+
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            read_request_headers(&mut stream).await;
+            stream
+                .write_all(
+                    b"HTTP/1.1 200 OK\r\n\
+Content-Type: application/json\r\n\
+Transfer-Encoding: chunked\r\n\
+Connection: keep-alive\r\n\
+\r\n\
+21\r\n\
+{\"status\":\"ok\",\"service\":\"mappy\"}\r\n\
+0\r\n\
+\r\n",
+                )
+                .await
+                .unwrap();
+
+            read_request_headers(&mut stream).await;
+            let body = b"{\"status\":\"ok\",\"service\":\"mappy\"}";
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: keep-alive\r\n\r\n",
+                body.len()
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+            stream.write_all(body).await.unwrap();
+        });
+
+        let client = Client::with_config(
+            ClientConfig::with_socket(&socket_path).with_keep_alive(true, 4, StdDuration::from_secs(90)),
+        );
+
+        let first = client.health().await.unwrap();
+        assert_eq!(first.status, "ok");
+        let second = client.health().await.unwrap();
+        assert_eq!(second.status, "ok");
+
+        let _ = std::fs::remove_file(&socket_path);
+    }
+
+    async fn read_request_headers(stream: &mut UnixStream) {
+        let mut buf = Vec::new();
+        let mut scratch = [0u8; 4096];
+        loop {
+            if find_subslice(&buf, b"\r\n\r\n").is_some() {
+                return;
+            }
+            let n = stream.read(&mut scratch).await.unwrap();
+            assert!(n > 0, "connection closed before request headers completed");
+            buf.extend_from_slice(&scratch[..n]);
+        }
+    }
 }
@@ -6,6 +6,10 @@ use pyo3::prelude::*;
 use mappy_core::{Maplet, CounterOperator};
 use std::sync::Arc;
 use tokio::runtime::Runtime;
+use stilts::compression::{
+    ArithmeticCompressor, CompressionMethod, Compressor, DictionaryCompressor, FsstCompressor,
+    HuffmanCompressor, Registry,
+};
 
 /// Python wrapper for Maplet
 #[pyclass]
@@ -67,9 +71,73 @@ impl PyMaplet {
     }
 }
 
+fn py_value_error(e: impl std::fmt::Display) -> PyErr {
+    PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string())
+}
+
+/// Trains and holds one instance of each `stilts` compressor backend, so a Python caller
+/// that already depends on `mappy_python` for `PyMaplet` can train a model on its tag
+/// corpus and round-trip data without a second binding package.
+#[pyclass]
+pub struct PyCompressor {
+    huffman: HuffmanCompressor,
+    arithmetic: ArithmeticCompressor,
+    dictionary: DictionaryCompressor,
+    fsst: FsstCompressor,
+}
+
+#[pymethods]
+impl PyCompressor {
+    #[new]
+    fn new() -> Self {
+        Self {
+            huffman: HuffmanCompressor::new(),
+            arithmetic: ArithmeticCompressor::new(),
+            dictionary: DictionaryCompressor::new(),
+            fsst: FsstCompressor::new(),
+        }
+    }
+
+    /// Train `method`'s backend ("huffman", "arithmetic", "dictionary", or "fsst") on `corpus`.
+    fn build_from_corpus(&mut self, corpus: Vec<String>, method: &str) -> PyResult<()> {
+        match method.parse::<CompressionMethod>().map_err(py_value_error)? {
+            CompressionMethod::Huffman => self.huffman.build_from_corpus(&corpus).map_err(py_value_error),
+            CompressionMethod::Arithmetic => self.arithmetic.build_from_corpus(&corpus).map_err(py_value_error),
+            CompressionMethod::Dictionary => self.dictionary.build_from_corpus(&corpus).map_err(py_value_error),
+            CompressionMethod::Fsst => self.fsst.train(&corpus).map_err(py_value_error),
+            other => Err(py_value_error(format!("No Python binding for method: {}", other))),
+        }
+    }
+
+    /// Compress `tags` with `method`'s backend (training it on `tags` first if it hasn't
+    /// been trained yet).
+    fn compress(&mut self, tags: Vec<String>, method: &str) -> PyResult<Vec<u8>> {
+        match method.parse::<CompressionMethod>().map_err(py_value_error)? {
+            CompressionMethod::Huffman => self.huffman.compress(&tags).map_err(py_value_error),
+            CompressionMethod::Arithmetic => self.arithmetic.compress(&tags).map_err(py_value_error),
+            CompressionMethod::Dictionary => self.dictionary.compress(&tags).map_err(py_value_error),
+            CompressionMethod::Fsst => self.fsst.compress(&tags).map_err(py_value_error),
+            other => Err(py_value_error(format!("No Python binding for method: {}", other))),
+        }
+    }
+
+    /// Decompress a blob produced by any of this instance's trained backends, regardless
+    /// of which one, by reading the blob's leading method-ID byte rather than requiring
+    /// the caller to track which algorithm compressed it.
+    fn decompress(&self, data: Vec<u8>) -> PyResult<Vec<String>> {
+        let mut registry = Registry::new();
+        registry.register(CompressionMethod::Huffman, Box::new(self.huffman.clone()));
+        registry.register(CompressionMethod::Arithmetic, Box::new(self.arithmetic.clone()));
+        registry.register(CompressionMethod::Dictionary, Box::new(self.dictionary.clone()));
+        registry.register(CompressionMethod::Fsst, Box::new(self.fsst.clone()));
+        registry.decode_any(&data).map_err(py_value_error)
+    }
+}
+
 /// Python module definition
 #[pymodule]
 fn mappy_python(_py: Python, m: &Bound<PyModule>) -> PyResult<()> {
     m.add_class::<PyMaplet>()?;
+    m.add_class::<PyCompressor>()?;
     Ok(())
 }
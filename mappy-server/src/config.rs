@@ -0,0 +1,116 @@
+//! Server configuration, loadable from environment variables or a TOML file
+//!
+//! This backs hot-reload: a `ServerSettings` is parsed once at startup (from
+//! `MAPPY_CONFIG_FILE` if set, otherwise from env vars) and again on every
+//! reload trigger, so the two paths must agree on defaults.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+/// Tunable engine settings that can change across a hot reload.
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ServerSettings {
+    pub capacity: usize,
+    pub false_positive_rate: f64,
+    pub data_dir: String,
+    pub persistence_mode: String,
+    pub value_compression: Option<String>,
+}
+
+impl Default for ServerSettings {
+    fn default() -> Self {
+        Self {
+            capacity: 10_000,
+            false_positive_rate: 0.01,
+            data_dir: "./data/mappy".to_string(),
+            persistence_mode: "memory".to_string(),
+            value_compression: None,
+        }
+    }
+}
+
+impl ServerSettings {
+    /// Load settings from environment variables (the original, non-reloadable behavior).
+    pub fn from_env() -> Result<Self> {
+        let defaults = Self::default();
+
+        let capacity = std::env::var("MAPPY_CAPACITY")
+            .ok()
+            .map(|v| v.parse::<usize>())
+            .transpose()?
+            .unwrap_or(defaults.capacity);
+        let false_positive_rate = std::env::var("MAPPY_FALSE_POSITIVE_RATE")
+            .ok()
+            .map(|v| v.parse::<f64>())
+            .transpose()?
+            .unwrap_or(defaults.false_positive_rate);
+        let data_dir = std::env::var("MAPPY_DATA_DIR").unwrap_or(defaults.data_dir);
+        let persistence_mode =
+            std::env::var("MAPPY_PERSISTENCE_MODE").unwrap_or(defaults.persistence_mode);
+        let value_compression = std::env::var("MAPPY_VALUE_COMPRESSION").ok();
+
+        Ok(Self {
+            capacity,
+            false_positive_rate,
+            data_dir,
+            persistence_mode,
+            value_compression,
+        })
+    }
+
+    /// Load settings from a TOML file, falling back to env vars for anything unset.
+    pub fn load(config_path: Option<&str>) -> Result<Self> {
+        let base = Self::from_env()?;
+
+        let Some(path) = config_path else {
+            return Ok(base);
+        };
+
+        if !std::path::Path::new(path).exists() {
+            return Ok(base);
+        }
+
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file: {}", path))?;
+        let overrides: TomlOverrides = toml::from_str(&contents)
+            .with_context(|| format!("Failed to parse config file as TOML: {}", path))?;
+        Ok(overrides.apply_to(base))
+    }
+}
+
+/// A partial `ServerSettings`: every field is `Option`, so a TOML file that only sets a few
+/// keys leaves the rest as `None` (serde's default behavior for bare `Option<T>` fields,
+/// no `#[serde(default)]` needed) rather than falling back to `ServerSettings::default()`,
+/// which would silently discard env-var overrides `load` already applied.
+#[derive(Debug, Deserialize)]
+struct TomlOverrides {
+    capacity: Option<usize>,
+    false_positive_rate: Option<f64>,
+    data_dir: Option<String>,
+    persistence_mode: Option<String>,
+    value_compression: Option<String>,
+}
+
+impl TomlOverrides {
+    /// Layer the fields this file actually set on top of `base` (normally `from_env()`),
+    /// leaving anything it omitted untouched.
+    fn apply_to(self, mut base: ServerSettings) -> ServerSettings {
+        if let Some(v) = self.capacity {
+            base.capacity = v;
+        }
+        if let Some(v) = self.false_positive_rate {
+            base.false_positive_rate = v;
+        }
+        if let Some(v) = self.data_dir {
+            base.data_dir = v;
+        }
+        if let Some(v) = self.persistence_mode {
+            base.persistence_mode = v;
+        }
+        if let Some(v) = self.value_compression {
+            base.value_compression = Some(v);
+        }
+        base
+    }
+}
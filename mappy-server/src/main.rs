@@ -1,27 +1,60 @@
 //! Mappy Server - Network server for the Mappy service
 
+mod config;
+mod response_compression;
+mod value_compression;
+
 use axum::{
     Router,
-    extract::{Path, State},
-    http::StatusCode,
-    response::Json,
+    body::Body,
+    extract::{Path, Query, Request, State},
+    http::{header, StatusCode},
+    middleware,
+    response::{Json, Response},
     routing::{get as get_route, post},
 };
+use anyhow::Context;
+use config::ServerSettings;
+use futures::StreamExt;
 use mappy_core::{Engine, EngineConfig, PersistenceMode};
 use serde::{Deserialize, Serialize};
+use stilts::formats::{CommaSeparatedParser, TagParser, parser_for};
+use stilts::mappy_integration::{MappyStorageMetrics, MappyTagStorage};
+use value_compression::{ValueAlgorithm, ValueCompressionState};
+use std::collections::HashMap;
 use std::fs;
+use std::io::BufReader;
 use std::os::unix::fs::PermissionsExt;
 use std::path::Path as StdPath;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::net::{TcpListener, UnixListener};
+use tokio::signal::unix::{SignalKind, signal};
 use tokio::sync::RwLock;
+use tokio_rustls::TlsAcceptor;
+use tokio_rustls::rustls::ServerConfig as RustlsServerConfig;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
 use tower::Service;
 use tracing::info;
 
 #[derive(Clone)]
 struct AppState {
     engine: Arc<RwLock<Option<Engine>>>,
+    /// Bumped on every successful hot reload; surfaced via `/status` so
+    /// operators can confirm a `SIGHUP` actually swapped the engine.
+    generation: Arc<AtomicU64>,
+    /// Path to the TOML config file, if one was given at startup. `None` means
+    /// reload falls back to re-reading environment variables.
+    config_path: Option<Arc<String>>,
+    /// `Some` once `MAPPY_VALUE_COMPRESSION` (or a reload) selects an algorithm; `set`/`get`
+    /// then route values through it instead of storing them verbatim. The outer lock is
+    /// always present (mirroring `engine`'s `Arc<RwLock<Option<_>>>`) so `reload_engine` can
+    /// turn this on or switch algorithms without a restart, even if it started as `None`.
+    value_compression: Arc<RwLock<Option<ValueCompressionState>>>,
+    /// Corpora trained via `POST /corpus`, keyed by the id handed back to the client.
+    corpora: Arc<RwLock<HashMap<String, MappyTagStorage>>>,
+    corpus_counter: Arc<AtomicU64>,
 }
 
 #[derive(Serialize)]
@@ -34,6 +67,7 @@ struct HealthResponse {
 struct StatusResponse {
     status: String,
     engine_ready: bool,
+    generation: u64,
 }
 
 #[derive(Deserialize)]
@@ -61,6 +95,7 @@ async fn status(State(state): State<AppState>) -> Json<StatusResponse> {
     Json(StatusResponse {
         status: "running".to_string(),
         engine_ready,
+        generation: state.generation.load(Ordering::Acquire),
     })
 }
 
@@ -70,8 +105,14 @@ async fn set(
 ) -> Result<StatusCode, StatusCode> {
     let engine_guard = state.engine.read().await;
     if let Some(ref engine) = *engine_guard {
+        let stored = match &mut *state.value_compression.write().await {
+            Some(vc) => vc
+                .encode(&request.value)
+                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?,
+            None => request.value.as_bytes().to_vec(),
+        };
         engine
-            .set(request.key.clone(), request.value.as_bytes().to_vec())
+            .set(request.key.clone(), stored)
             .await
             .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
         Ok(StatusCode::OK)
@@ -87,11 +128,19 @@ async fn get_value(
     let engine_guard = state.engine.read().await;
     if let Some(ref engine) = *engine_guard {
         match engine.get(&key).await {
-            Ok(Some(value)) => Ok(Json(GetResponse {
-                key,
-                value: Some(String::from_utf8_lossy(&value).to_string()),
-                found: true,
-            })),
+            Ok(Some(raw)) => {
+                let value = match &*state.value_compression.read().await {
+                    Some(vc) => vc
+                        .decode(&raw)
+                        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?,
+                    None => String::from_utf8_lossy(&raw).to_string(),
+                };
+                Ok(Json(GetResponse {
+                    key,
+                    value: Some(value),
+                    found: true,
+                }))
+            }
             Ok(None) => Ok(Json(GetResponse {
                 key,
                 value: None,
@@ -104,6 +153,288 @@ async fn get_value(
     }
 }
 
+#[derive(Serialize)]
+struct MetricsResponse {
+    enabled: bool,
+    algorithm: Option<&'static str>,
+    corpus_version: Option<u8>,
+    entries: u64,
+    original_bytes: u64,
+    compressed_bytes: u64,
+    stored_bytes: u64,
+    compression_ratio: f64,
+}
+
+async fn metrics(State(state): State<AppState>) -> Json<MetricsResponse> {
+    let guard = state.value_compression.read().await;
+    let Some(vc) = &*guard else {
+        return Json(MetricsResponse {
+            enabled: false,
+            algorithm: None,
+            corpus_version: None,
+            entries: 0,
+            original_bytes: 0,
+            compressed_bytes: 0,
+            stored_bytes: 0,
+            compression_ratio: 0.0,
+        });
+    };
+
+    let m = vc.metrics();
+    Json(MetricsResponse {
+        enabled: true,
+        algorithm: Some(vc.algorithm_name()),
+        corpus_version: Some(vc.version()),
+        entries: m.entries,
+        original_bytes: m.original_bytes,
+        compressed_bytes: m.compressed_bytes,
+        stored_bytes: m.stored_bytes,
+        compression_ratio: m.compression_ratio(),
+    })
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+enum BatchOp {
+    Set { key: String, value: String },
+    Get { key: String },
+}
+
+#[derive(Serialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+enum BatchResult {
+    Set {
+        key: String,
+        ok: bool,
+    },
+    Get {
+        key: String,
+        value: Option<String>,
+        found: bool,
+    },
+}
+
+/// Marks a batch-stored value as the raw bytes the caller sent, with no tag compression applied.
+const BATCH_RAW_MARKER: u8 = 0;
+/// Marks a batch-stored value as tag-compressed via `MappyTagStorage::with_dictionary`.
+const BATCH_TAGS_MARKER: u8 = 1;
+
+/// If `value` parses as more than one comma-separated tag, compress it with
+/// `MappyTagStorage` before handing it to the engine; otherwise store it as-is.
+/// Dictionary compression is used because its format self-embeds the dictionary,
+/// so any later `decode_batch_value` call can decompress it without shared state.
+fn encode_batch_value(value: &str) -> anyhow::Result<Vec<u8>> {
+    let tags = CommaSeparatedParser::new().parse(value)?;
+    if tags.len() > 1 {
+        let mut storage = MappyTagStorage::with_dictionary();
+        let compressed = storage.compress_tags_with_corpus(&tags)?;
+        let mut encoded = Vec::with_capacity(compressed.len() + 1);
+        encoded.push(BATCH_TAGS_MARKER);
+        encoded.extend(compressed);
+        Ok(encoded)
+    } else {
+        let mut encoded = Vec::with_capacity(value.len() + 1);
+        encoded.push(BATCH_RAW_MARKER);
+        encoded.extend(value.as_bytes());
+        Ok(encoded)
+    }
+}
+
+/// Inverse of `encode_batch_value`.
+fn decode_batch_value(data: &[u8]) -> anyhow::Result<String> {
+    let Some((&marker, rest)) = data.split_first() else {
+        return Ok(String::new());
+    };
+    match marker {
+        BATCH_TAGS_MARKER => {
+            let tags = MappyTagStorage::with_dictionary().decompress_tags(rest)?;
+            Ok(tags.join(","))
+        }
+        _ => Ok(String::from_utf8_lossy(rest).to_string()),
+    }
+}
+
+async fn batch(
+    State(state): State<AppState>,
+    Json(ops): Json<Vec<BatchOp>>,
+) -> Result<Json<Vec<BatchResult>>, StatusCode> {
+    let engine_guard = state.engine.read().await;
+    let Some(ref engine) = *engine_guard else {
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
+    };
+
+    let mut results = Vec::with_capacity(ops.len());
+    for op in ops {
+        match op {
+            BatchOp::Set { key, value } => {
+                let encoded =
+                    encode_batch_value(&value).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+                let ok = engine.set(key.clone(), encoded).await.is_ok();
+                results.push(BatchResult::Set { key, ok });
+            }
+            BatchOp::Get { key } => match engine.get(&key).await {
+                Ok(Some(raw)) => {
+                    let value = decode_batch_value(&raw).ok();
+                    let found = value.is_some();
+                    results.push(BatchResult::Get { key, value, found });
+                }
+                Ok(None) => results.push(BatchResult::Get {
+                    key,
+                    value: None,
+                    found: false,
+                }),
+                Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+            },
+        }
+    }
+
+    Ok(Json(results))
+}
+
+/// Stream a `BatchResult::Get` as a newline-delimited JSON (ndjson) frame per requested key,
+/// rather than buffering the whole result set into one `Json<Vec<_>>` response the way
+/// `batch` does. Mappy's maplet is an approximate, non-enumerable structure, so this takes
+/// the explicit key list to fetch rather than scanning "every key" — it's a streaming-decode
+/// `mget`, which is what `Client::scan` actually needs for bulk-dumping a known keyspace
+/// without materializing every row in memory at once.
+async fn scan(
+    State(state): State<AppState>,
+    Json(keys): Json<Vec<String>>,
+) -> Result<Response, StatusCode> {
+    if state.engine.read().await.is_none() {
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    let stream = futures::stream::unfold((keys.into_iter(), state), |(mut keys, state)| async move {
+        let key = keys.next()?;
+        let engine_guard = state.engine.read().await;
+        let result = match *engine_guard {
+            Some(ref engine) => match engine.get(&key).await {
+                Ok(Some(raw)) => {
+                    let value = decode_batch_value(&raw).ok();
+                    let found = value.is_some();
+                    BatchResult::Get { key, value, found }
+                }
+                _ => BatchResult::Get {
+                    key,
+                    value: None,
+                    found: false,
+                },
+            },
+            None => BatchResult::Get {
+                key,
+                value: None,
+                found: false,
+            },
+        };
+        drop(engine_guard);
+
+        let mut line = serde_json::to_vec(&result).ok()?;
+        line.push(b'\n');
+        Some((Ok::<_, std::io::Error>(line), (keys, state)))
+    });
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/x-ndjson")
+        .body(Body::from_stream(stream))
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+#[derive(Deserialize)]
+struct CorpusQuery {
+    format: Option<String>,
+}
+
+#[derive(Serialize)]
+struct CorpusResponse {
+    corpus_id: String,
+    metrics: MappyStorageMetrics,
+}
+
+/// Stream the request body into tags without buffering the whole upload as one string.
+/// Space/comma formats are line-oriented, so each complete line is parsed as it arrives;
+/// `json` expects a single top-level array and so is necessarily parsed once the body ends.
+async fn collect_streamed_tags(body: Body, parser: &dyn TagParser, format: &str) -> anyhow::Result<Vec<String>> {
+    let mut stream = body.into_data_stream();
+    let mut buffer = String::new();
+    let mut tags = Vec::new();
+
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        buffer.push_str(&String::from_utf8_lossy(&chunk));
+
+        if format != "json" {
+            while let Some(pos) = buffer.find('\n') {
+                let line = buffer[..pos].to_string();
+                buffer.drain(..=pos);
+                if !line.trim().is_empty() {
+                    tags.extend(parser.parse(&line)?);
+                }
+            }
+        }
+    }
+
+    if format == "json" {
+        tags.extend(parser.parse(&buffer)?);
+    } else if !buffer.trim().is_empty() {
+        tags.extend(parser.parse(&buffer)?);
+    }
+
+    Ok(tags)
+}
+
+async fn ingest_corpus(
+    State(state): State<AppState>,
+    Query(params): Query<CorpusQuery>,
+    request: Request,
+) -> Result<Json<CorpusResponse>, StatusCode> {
+    let format = params.format.unwrap_or_else(|| "space".to_string());
+    let parser = parser_for(&format).unwrap_or_else(|| parser_for("space").unwrap());
+
+    let tags = collect_streamed_tags(request.into_body(), parser.as_ref(), &format)
+        .await
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let mut storage = MappyTagStorage::with_huffman();
+    storage
+        .build_corpus(&tags)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let compressed = storage
+        .compress_tags(&tags)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let original_size: usize = tags.iter().map(|t| t.len() + 1).sum();
+    let compressed_size = compressed.len();
+    let compression_ratio = if original_size == 0 {
+        0.0
+    } else {
+        compressed_size as f64 / original_size as f64
+    };
+
+    let corpus_id = format!(
+        "corpus-{}",
+        state.corpus_counter.fetch_add(1, Ordering::AcqRel)
+    );
+    state
+        .corpora
+        .write()
+        .await
+        .insert(corpus_id.clone(), storage);
+
+    Ok(Json(CorpusResponse {
+        metrics: MappyStorageMetrics {
+            key: corpus_id.clone(),
+            original_size,
+            compressed_size,
+            mappy_storage_size: compressed_size,
+            compression_ratio,
+            total_storage_ratio: 1.0,
+        },
+        corpus_id,
+    }))
+}
+
 // Adapter to make UnixStream work with hyper/axum
 struct UnixStreamAdapter(tokio::net::UnixStream);
 
@@ -172,6 +503,182 @@ async fn serve_unix_socket(listener: UnixListener, app: Router) -> anyhow::Resul
     }
 }
 
+// Load a PEM certificate chain and private key into a rustls ServerConfig
+fn load_tls_config(cert_path: &str, key_path: &str) -> anyhow::Result<RustlsServerConfig> {
+    // rustls 0.23's ServerConfig::builder() panics unless a process-level default crypto
+    // provider has been installed first. Installing is idempotent from our side: the only
+    // failure mode is "a provider is already installed", which is fine to ignore.
+    let _ = tokio_rustls::rustls::crypto::ring::default_provider().install_default();
+
+    let cert_file = fs::File::open(cert_path)
+        .with_context(|| format!("Failed to open TLS cert file: {}", cert_path))?;
+    let mut cert_reader = BufReader::new(cert_file);
+    let certs: Vec<CertificateDer<'static>> = rustls_pemfile::certs(&mut cert_reader)
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("Failed to parse TLS cert chain: {}", cert_path))?;
+
+    let key_file = fs::File::open(key_path)
+        .with_context(|| format!("Failed to open TLS key file: {}", key_path))?;
+    let mut key_reader = BufReader::new(key_file);
+    let key: PrivateKeyDer<'static> = rustls_pemfile::private_key(&mut key_reader)
+        .with_context(|| format!("Failed to parse TLS private key: {}", key_path))?
+        .context("No private key found in TLS key file")?;
+
+    let config = RustlsServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .context("Failed to build rustls ServerConfig")?;
+
+    Ok(config)
+}
+
+// Turn tunable `ServerSettings` into the `EngineConfig` the engine actually consumes.
+fn build_engine_config(settings: &ServerSettings) -> EngineConfig {
+    let persistence = match settings.persistence_mode.as_str() {
+        "disk" => PersistenceMode::Disk,
+        "hybrid" => PersistenceMode::Hybrid,
+        _ => PersistenceMode::Memory,
+    };
+
+    EngineConfig {
+        persistence_mode: persistence,
+        data_dir: Some(settings.data_dir.clone()),
+        maplet: mappy_core::types::MapletConfig {
+            capacity: settings.capacity,
+            false_positive_rate: settings.false_positive_rate,
+            max_load_factor: 0.95,
+            auto_resize: true,
+            enable_deletion: true,
+            enable_merging: true,
+        },
+        storage: mappy_core::storage::StorageConfig::default(),
+        ttl: mappy_core::ttl::TTLConfig::default(),
+    }
+}
+
+// Re-read config, build a fresh `Engine`, and swap it in behind the write lock so
+// in-flight reads drain against the old engine while new reads see the new one. Also
+// reconciles `value_compression` against the reloaded settings, so "compressor choice"
+// (named in the original hot-reload request) can be turned on or switched without a restart.
+async fn reload_engine(state: &AppState) -> anyhow::Result<()> {
+    let settings = ServerSettings::load(state.config_path.as_deref().map(|s| s.as_str()))?;
+    let new_config = build_engine_config(&settings);
+    let new_engine = Engine::new(new_config).await?;
+
+    let mut engine_guard = state.engine.write().await;
+    *engine_guard = Some(new_engine);
+    drop(engine_guard);
+
+    reload_value_compression(state, &settings).await?;
+
+    let generation = state.generation.fetch_add(1, Ordering::AcqRel) + 1;
+    info!("Engine reloaded, now at generation {}", generation);
+    Ok(())
+}
+
+// If the reloaded settings name a value-compression algorithm that isn't already active
+// (either none was active yet, or the algorithm changed), start a fresh `ValueCompressionState`
+// for it. There's no TOML/env way to express "disable it", so an already-active compressor is
+// left alone when the reloaded settings no longer name one.
+async fn reload_value_compression(state: &AppState, settings: &ServerSettings) -> anyhow::Result<()> {
+    let Some(algorithm) = settings.value_compression.as_deref().and_then(ValueAlgorithm::parse)
+    else {
+        return Ok(());
+    };
+
+    let mut vc_guard = state.value_compression.write().await;
+    if vc_guard.as_ref().is_some_and(|vc| vc.algorithm() == algorithm) {
+        return Ok(());
+    }
+
+    let vc = ValueCompressionState::new(algorithm, &settings.data_dir)?;
+    info!("Value compression (re)enabled with algorithm {}", vc.algorithm_name());
+    *vc_guard = Some(vc);
+    Ok(())
+}
+
+// Periodically persist the accumulated corpus and retrain the active compressor, once one is
+// active. Spawned unconditionally (unlike before) since `reload_engine` can now turn
+// compression on after startup.
+async fn watch_corpus_refresh(vc: Arc<RwLock<Option<ValueCompressionState>>>, interval_secs: u64) {
+    let mut ticker = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+    ticker.tick().await; // first tick fires immediately; skip it
+
+    loop {
+        ticker.tick().await;
+        let mut guard = vc.write().await;
+        let Some(vc) = &mut *guard else {
+            continue;
+        };
+        match vc.refresh_corpus() {
+            Ok(()) => info!("Value-compression corpus refreshed to version {}", vc.version()),
+            Err(e) => tracing::error!("Value-compression corpus refresh failed: {}", e),
+        }
+    }
+}
+
+// Listen for SIGHUP and reload the engine each time it arrives.
+async fn watch_for_reload(state: AppState) {
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(s) => s,
+        Err(e) => {
+            tracing::error!("Failed to install SIGHUP handler: {}", e);
+            return;
+        }
+    };
+
+    loop {
+        hangup.recv().await;
+        info!("Received SIGHUP, reloading engine configuration");
+        if let Err(e) = reload_engine(&state).await {
+            tracing::error!("Engine reload failed: {}", e);
+        }
+    }
+}
+
+// Accept TCP connections, terminate TLS, then serve them the same way serve_unix_socket does
+async fn serve_tls(
+    listener: TcpListener,
+    acceptor: TlsAcceptor,
+    app: Router,
+) -> anyhow::Result<()> {
+    use hyper::service::service_fn;
+    use hyper_util::rt::TokioIo;
+    use hyper_util::server::conn::auto::Builder;
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let app = app.clone();
+        let acceptor = acceptor.clone();
+
+        tokio::spawn(async move {
+            let tls_stream = match acceptor.accept(stream).await {
+                Ok(s) => s,
+                Err(e) => {
+                    tracing::error!("TLS handshake failed: {}", e);
+                    return;
+                }
+            };
+
+            let io = TokioIo::new(tls_stream);
+            let svc = service_fn(move |req| {
+                let mut app = app.clone();
+                async move {
+                    app.call(req).await.map_err(|e| {
+                        std::io::Error::new(std::io::ErrorKind::Other, format!("{}", e))
+                    })
+                }
+            });
+
+            let builder = Builder::new(hyper_util::rt::TokioExecutor::new());
+
+            if let Err(e) = builder.serve_connection(io, svc).await {
+                tracing::error!("Error serving TLS connection: {}", e);
+            }
+        });
+    }
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     // Initialize tracing
@@ -197,15 +704,18 @@ async fn main() -> anyhow::Result<()> {
         .unwrap_or_else(|_| "8003".to_string())
         .parse::<u16>()?;
     let host = std::env::var("MAPPY_HOST").unwrap_or_else(|_| "0.0.0.0".to_string());
-    let capacity = std::env::var("MAPPY_CAPACITY")
-        .unwrap_or_else(|_| "10000".to_string())
-        .parse::<usize>()?;
-    let false_positive_rate = std::env::var("MAPPY_FALSE_POSITIVE_RATE")
-        .unwrap_or_else(|_| "0.01".to_string())
-        .parse::<f64>()?;
-    let data_dir = std::env::var("MAPPY_DATA_DIR").unwrap_or_else(|_| "./data/mappy".to_string());
-    let persistence_mode =
-        std::env::var("MAPPY_PERSISTENCE_MODE").unwrap_or_else(|_| "memory".to_string());
+    let config_path = std::env::var("MAPPY_CONFIG_FILE").ok();
+    let enable_tls = std::env::var("MAPPY_ENABLE_TLS")
+        .unwrap_or_else(|_| "false".to_string())
+        .parse::<bool>()
+        .unwrap_or(false);
+    let tls_cert = std::env::var("MAPPY_TLS_CERT").ok();
+    let tls_key = std::env::var("MAPPY_TLS_KEY").ok();
+    let tls_port = std::env::var("MAPPY_TLS_PORT")
+        .unwrap_or_else(|_| "8443".to_string())
+        .parse::<u16>()?;
+
+    let settings = ServerSettings::load(config_path.as_deref())?;
 
     info!(
         "Configuration: socket_path={}, enable_http={}, port={}, host={}, capacity={}, false_positive_rate={}, data_dir={}, persistence={}",
@@ -213,50 +723,62 @@ async fn main() -> anyhow::Result<()> {
         enable_http,
         port,
         host,
-        capacity,
-        false_positive_rate,
-        data_dir,
-        persistence_mode
+        settings.capacity,
+        settings.false_positive_rate,
+        settings.data_dir,
+        settings.persistence_mode
     );
 
     // Initialize engine
-    let persistence = match persistence_mode.as_str() {
-        "disk" => PersistenceMode::Disk,
-        "hybrid" => PersistenceMode::Hybrid,
-        _ => PersistenceMode::Memory,
-    };
+    let engine = Engine::new(build_engine_config(&settings)).await?;
 
-    let config = EngineConfig {
-        persistence_mode: persistence,
-        data_dir: Some(data_dir),
-        maplet: mappy_core::types::MapletConfig {
-            capacity,
-            false_positive_rate,
-            max_load_factor: 0.95,
-            auto_resize: true,
-            enable_deletion: true,
-            enable_merging: true,
-        },
-        storage: mappy_core::storage::StorageConfig::default(),
-        ttl: mappy_core::ttl::TTLConfig::default(),
-    };
+    let value_compression = settings
+        .value_compression
+        .as_deref()
+        .and_then(ValueAlgorithm::parse)
+        .map(|algorithm| ValueCompressionState::new(algorithm, &settings.data_dir))
+        .transpose()?;
 
-    let engine = Engine::new(config).await?;
     let state = AppState {
         engine: Arc::new(RwLock::new(Some(engine))),
+        generation: Arc::new(AtomicU64::new(0)),
+        config_path: config_path.map(Arc::new),
+        value_compression: Arc::new(RwLock::new(value_compression)),
+        corpora: Arc::new(RwLock::new(HashMap::new())),
+        corpus_counter: Arc::new(AtomicU64::new(0)),
     };
 
+    tokio::spawn(watch_for_reload(state.clone()));
+    let refresh_secs = std::env::var("MAPPY_VALUE_CORPUS_REFRESH_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(300);
+    tokio::spawn(watch_corpus_refresh(state.value_compression.clone(), refresh_secs));
+
     // Build router
     let app = Router::new()
         .route("/health", get_route(health))
         .route("/status", get_route(status))
         .route("/set", post(set))
         .route("/get/{key}", get_route(get_value))
-        .with_state(state);
+        .route("/batch", post(batch))
+        .route("/scan", post(scan))
+        .route("/metrics", get_route(metrics))
+        .route("/corpus", post(ingest_corpus))
+        .with_state(state)
+        .layer(middleware::from_fn(response_compression::compress_response));
+
+    if !enable_http && !enable_socket && !enable_tls {
+        return Err(anyhow::anyhow!(
+            "At least one of HTTP, socket, or TLS must be enabled"
+        ));
+    }
+
+    // Start each enabled transport as its own task and race them; the first one
+    // to exit (normally an error, since these loops run forever) ends the process.
+    let mut handles: Vec<tokio::task::JoinHandle<anyhow::Result<()>>> = Vec::new();
 
-    // Start servers based on configuration
-    if enable_http && enable_socket {
-        // Both HTTP and socket
+    if enable_socket {
         let socket_path_std = StdPath::new(&socket_path);
         if socket_path_std.exists() {
             fs::remove_file(socket_path_std)?;
@@ -275,57 +797,39 @@ async fn main() -> anyhow::Result<()> {
         fs::set_permissions(socket_path_std, perms)?;
         info!("Mappy Server Unix socket listening on {}", socket_path);
 
+        let app_clone = app.clone();
+        handles.push(tokio::spawn(async move {
+            serve_unix_socket(unix_listener, app_clone).await
+        }));
+    }
+
+    if enable_http {
         let http_listener = TcpListener::bind(format!("{}:{}", host, port)).await?;
         info!("Mappy Server HTTP listening on {}:{}", host, port);
 
         let app_clone = app.clone();
-        let socket_handle =
-            tokio::spawn(async move { serve_unix_socket(unix_listener, app_clone).await });
-
-        let http_handle = tokio::spawn(async move { axum::serve(http_listener, app).await });
+        handles.push(tokio::spawn(async move {
+            axum::serve(http_listener, app_clone).await.map_err(Into::into)
+        }));
+    }
 
-        tokio::select! {
-            result = socket_handle => {
-                if let Err(e) = result? {
-                    return Err(anyhow::anyhow!("Unix socket server error: {}", e));
-                }
-            }
-            result = http_handle => {
-                if let Err(e) = result? {
-                    return Err(anyhow::anyhow!("HTTP server error: {}", e));
-                }
-            }
-        }
-    } else if enable_http {
-        // HTTP only
-        let http_listener = TcpListener::bind(format!("{}:{}", host, port)).await?;
-        info!("Mappy Server HTTP listening on {}:{}", host, port);
-        axum::serve(http_listener, app).await?;
-    } else if enable_socket {
-        // Socket only
-        let socket_path_std = StdPath::new(&socket_path);
-        if socket_path_std.exists() {
-            fs::remove_file(socket_path_std)?;
-        }
+    if enable_tls {
+        let cert_path = tls_cert.context("MAPPY_ENABLE_TLS is set but MAPPY_TLS_CERT is missing")?;
+        let key_path = tls_key.context("MAPPY_ENABLE_TLS is set but MAPPY_TLS_KEY is missing")?;
+        let tls_config = load_tls_config(&cert_path, &key_path)?;
+        let acceptor = TlsAcceptor::from(Arc::new(tls_config));
 
-        if let Some(parent) = socket_path_std.parent() {
-            fs::create_dir_all(parent)?;
-            let mut dir_perms = fs::metadata(parent)?.permissions();
-            dir_perms.set_mode(0o775);
-            fs::set_permissions(parent, dir_perms)?;
-        }
+        let tls_listener = TcpListener::bind(format!("{}:{}", host, tls_port)).await?;
+        info!("Mappy Server HTTPS listening on {}:{}", host, tls_port);
 
-        let unix_listener = UnixListener::bind(socket_path_std)?;
-        let mut perms = fs::metadata(socket_path_std)?.permissions();
-        perms.set_mode(0o664);
-        fs::set_permissions(socket_path_std, perms)?;
-        info!("Mappy Server Unix socket listening on {}", socket_path);
-        serve_unix_socket(unix_listener, app).await?;
-    } else {
-        return Err(anyhow::anyhow!(
-            "At least one of HTTP or socket must be enabled"
-        ));
+        handles.push(tokio::spawn(async move {
+            serve_tls(tls_listener, acceptor, app).await
+        }));
     }
 
-    Ok(())
+    let (result, _index, _remaining) = futures::future::select_all(handles).await;
+    match result? {
+        Ok(()) => Ok(()),
+        Err(e) => Err(anyhow::anyhow!("Server transport error: {}", e)),
+    }
 }
@@ -0,0 +1,222 @@
+//! Negotiated response compression
+//!
+//! Inspects the client's `Accept-Encoding` header and compresses JSON response bodies with
+//! `flate2`'s `gzip`/`deflate`. Only codecs `mappy-client` (or any other caller) can actually
+//! decode are negotiated here — there's no `mappy-huffman`/`mappy-dictionary` token, since
+//! nothing in the repo exposes a decoder for those on the receiving end.
+
+use axum::body::{to_bytes, Body};
+use axum::extract::Request;
+use axum::http::{header, HeaderValue};
+use axum::middleware::Next;
+use axum::response::Response;
+use flate2::write::{DeflateEncoder, GzEncoder};
+use flate2::Compression;
+use std::io::Write;
+
+/// Bodies smaller than this are left alone; compressing them tends to grow them.
+const MIN_COMPRESSIBLE_LEN: usize = 64;
+
+enum Encoding {
+    Gzip,
+    Deflate,
+}
+
+impl Encoding {
+    fn token(&self) -> &'static str {
+        match self {
+            Encoding::Gzip => "gzip",
+            Encoding::Deflate => "deflate",
+        }
+    }
+}
+
+/// Pick the best encoding this server supports from a client's `Accept-Encoding` header.
+fn negotiate(accept_encoding: &str) -> Option<Encoding> {
+    let offered: Vec<&str> = accept_encoding
+        .split(',')
+        .map(|s| s.split(';').next().unwrap_or("").trim())
+        .collect();
+
+    if offered.contains(&"gzip") {
+        Some(Encoding::Gzip)
+    } else if offered.contains(&"deflate") {
+        Some(Encoding::Deflate)
+    } else {
+        None
+    }
+}
+
+fn is_already_compressed(content_type: &str) -> bool {
+    content_type.starts_with("image/")
+        || content_type.starts_with("video/")
+        || content_type.starts_with("audio/")
+        || content_type.contains("gzip")
+        || content_type.contains("zip")
+}
+
+/// Axum middleware that negotiates and applies response compression.
+pub async fn compress_response(request: Request, next: Next) -> Response {
+    let accept_encoding = request
+        .headers()
+        .get(header::ACCEPT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+
+    let response = next.run(request).await;
+
+    let Some(accept_encoding) = accept_encoding else {
+        return response;
+    };
+    let Some(encoding) = negotiate(&accept_encoding) else {
+        return response;
+    };
+
+    let content_type = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+    if is_already_compressed(&content_type) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = to_bytes(body, usize::MAX).await else {
+        return Response::from_parts(parts, Body::empty());
+    };
+
+    if bytes.len() < MIN_COMPRESSIBLE_LEN {
+        return Response::from_parts(parts, Body::from(bytes));
+    }
+
+    let compressed = match &encoding {
+        Encoding::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            if encoder.write_all(&bytes).is_err() {
+                return Response::from_parts(parts, Body::from(bytes));
+            }
+            encoder.finish().ok()
+        }
+        Encoding::Deflate => {
+            let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+            if encoder.write_all(&bytes).is_err() {
+                return Response::from_parts(parts, Body::from(bytes));
+            }
+            encoder.finish().ok()
+        }
+    };
+
+    let Some(compressed) = compressed else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+
+    parts
+        .headers
+        .insert(header::CONTENT_ENCODING, HeaderValue::from_static(encoding.token()));
+    parts
+        .headers
+        .insert(header::VARY, HeaderValue::from_static("Accept-Encoding"));
+    parts.headers.remove(header::CONTENT_LENGTH);
+
+    Response::from_parts(parts, Body::from(compressed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::{Request as HttpRequest, StatusCode};
+    use axum::middleware::from_fn;
+    use axum::routing::get;
+    use axum::Router;
+    use flate2::read::GzDecoder;
+    use std::io::Read as _;
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_negotiate_prefers_gzip_over_deflate() {
+        assert!(matches!(negotiate("gzip, deflate"), Some(Encoding::Gzip)));
+        assert!(matches!(negotiate("deflate, gzip"), Some(Encoding::Gzip)));
+    }
+
+    #[test]
+    fn test_negotiate_falls_back_to_deflate() {
+        assert!(matches!(negotiate("deflate"), Some(Encoding::Deflate)));
+    }
+
+    #[test]
+    fn test_negotiate_ignores_unsupported_tokens() {
+        assert!(negotiate("br").is_none());
+        assert!(negotiate("mappy-huffman, mappy-dictionary").is_none());
+        assert!(negotiate("").is_none());
+    }
+
+    #[test]
+    fn test_is_already_compressed() {
+        assert!(is_already_compressed("image/png"));
+        assert!(is_already_compressed("video/mp4"));
+        assert!(is_already_compressed("audio/mpeg"));
+        assert!(is_already_compressed("application/gzip"));
+        assert!(is_already_compressed("application/zip"));
+        assert!(!is_already_compressed("application/json"));
+    }
+
+    fn app() -> Router {
+        Router::new()
+            .route(
+                "/",
+                get(|| async { "x".repeat(MIN_COMPRESSIBLE_LEN * 2) }),
+            )
+            .layer(from_fn(compress_response))
+    }
+
+    #[tokio::test]
+    async fn test_compress_response_gzips_when_requested() {
+        let request = HttpRequest::builder()
+            .uri("/")
+            .header(header::ACCEPT_ENCODING, "gzip")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app().oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(header::CONTENT_ENCODING).unwrap(),
+            "gzip"
+        );
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let mut decoded = String::new();
+        GzDecoder::new(&body[..]).read_to_string(&mut decoded).unwrap();
+        assert_eq!(decoded, "x".repeat(MIN_COMPRESSIBLE_LEN * 2));
+    }
+
+    #[tokio::test]
+    async fn test_compress_response_passes_through_without_accept_encoding() {
+        let request = HttpRequest::builder()
+            .uri("/")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app().oneshot(request).await.unwrap();
+
+        assert!(response.headers().get(header::CONTENT_ENCODING).is_none());
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(body, "x".repeat(MIN_COMPRESSIBLE_LEN * 2).as_bytes());
+    }
+
+    #[tokio::test]
+    async fn test_compress_response_passes_through_unsupported_encoding() {
+        let request = HttpRequest::builder()
+            .uri("/")
+            .header(header::ACCEPT_ENCODING, "br")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app().oneshot(request).await.unwrap();
+
+        assert!(response.headers().get(header::CONTENT_ENCODING).is_none());
+    }
+}
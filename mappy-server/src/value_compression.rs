@@ -0,0 +1,352 @@
+//! At-rest value compression backed by a persisted, periodically-refreshed corpus
+//!
+//! When `MAPPY_VALUE_COMPRESSION` names an algorithm, `set` parses the incoming value
+//! into tags, compresses them with a shared `MappyTagStorage`, and prefixes the result
+//! with a small header recording the algorithm and corpus version so `get` knows how to
+//! reverse it. The corpus itself is persisted under `data_dir` so a restarted server can
+//! still decode values written against an earlier version.
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use stilts::formats::{CommaSeparatedParser, TagParser};
+use stilts::mappy_integration::MappyTagStorage;
+
+/// Which `MappyTagStorage` backend `MAPPY_VALUE_COMPRESSION` selected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueAlgorithm {
+    Huffman,
+    Arithmetic,
+    Dictionary,
+}
+
+impl ValueAlgorithm {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "huffman" => Some(Self::Huffman),
+            "arithmetic" => Some(Self::Arithmetic),
+            "dictionary" => Some(Self::Dictionary),
+            _ => None,
+        }
+    }
+
+    fn header_byte(self) -> u8 {
+        match self {
+            Self::Huffman => 0,
+            Self::Arithmetic => 1,
+            Self::Dictionary => 2,
+        }
+    }
+
+    fn from_header_byte(byte: u8) -> Result<Self> {
+        match byte {
+            0 => Ok(Self::Huffman),
+            1 => Ok(Self::Arithmetic),
+            2 => Ok(Self::Dictionary),
+            other => bail!("Unknown value-compression algorithm byte: {}", other),
+        }
+    }
+
+    fn new_storage(self) -> MappyTagStorage {
+        match self {
+            Self::Huffman => MappyTagStorage::with_huffman(),
+            Self::Arithmetic => MappyTagStorage::with_arithmetic(),
+            Self::Dictionary => MappyTagStorage::with_dictionary(),
+        }
+    }
+}
+
+/// Cumulative original/compressed/stored byte counts, exposed via `/metrics`.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct ValueCompressionMetrics {
+    pub entries: u64,
+    pub original_bytes: u64,
+    pub compressed_bytes: u64,
+    pub stored_bytes: u64,
+}
+
+impl ValueCompressionMetrics {
+    pub fn compression_ratio(&self) -> f64 {
+        if self.compressed_bytes == 0 {
+            0.0
+        } else {
+            self.original_bytes as f64 / self.compressed_bytes as f64
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct PersistedCorpus {
+    version: u8,
+    tags: Vec<String>,
+}
+
+/// Header prefixed to every at-rest-compressed value: `[algorithm, corpus_version]`.
+const HEADER_LEN: usize = 2;
+
+/// Shared state for at-rest value compression: the active trained compressor plus
+/// the accumulated corpus it was trained from.
+pub struct ValueCompressionState {
+    algorithm: ValueAlgorithm,
+    storage: MappyTagStorage,
+    corpus: HashSet<String>,
+    version: u8,
+    data_dir: PathBuf,
+    metrics: ValueCompressionMetrics,
+}
+
+impl ValueCompressionState {
+    /// Load the most recent persisted corpus for `algorithm` from `data_dir`, if any.
+    pub fn new(algorithm: ValueAlgorithm, data_dir: &str) -> Result<Self> {
+        let data_dir = PathBuf::from(data_dir);
+        std::fs::create_dir_all(&data_dir)
+            .with_context(|| format!("Failed to create data dir: {}", data_dir.display()))?;
+
+        let (version, tags) = Self::load_latest(&data_dir)?.unwrap_or((0, Vec::new()));
+
+        let mut storage = algorithm.new_storage();
+        if !tags.is_empty() {
+            storage.build_corpus(&tags)?;
+        }
+
+        Ok(Self {
+            algorithm,
+            storage,
+            corpus: tags.into_iter().collect(),
+            version,
+            data_dir,
+            metrics: ValueCompressionMetrics::default(),
+        })
+    }
+
+    fn corpus_path(data_dir: &Path, version: u8) -> PathBuf {
+        data_dir.join(format!("value_corpus_v{}.json", version))
+    }
+
+    /// Scan `data_dir` for the highest-numbered persisted corpus.
+    fn load_latest(data_dir: &Path) -> Result<Option<(u8, Vec<String>)>> {
+        let mut best: Option<(u8, Vec<String>)> = None;
+        let entries = match std::fs::read_dir(data_dir) {
+            Ok(entries) => entries,
+            Err(_) => return Ok(None),
+        };
+
+        for entry in entries.flatten() {
+            let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+                continue;
+            };
+            if !name.starts_with("value_corpus_v") || !name.ends_with(".json") {
+                continue;
+            }
+            let contents = std::fs::read_to_string(entry.path())?;
+            let persisted: PersistedCorpus = serde_json::from_str(&contents)?;
+            if best.as_ref().is_none_or(|(v, _)| persisted.version > *v) {
+                best = Some((persisted.version, persisted.tags));
+            }
+        }
+
+        Ok(best)
+    }
+
+    /// Load the corpus for a specific historical `version`, for decoding old values.
+    fn load_version(&self, version: u8) -> Result<Vec<String>> {
+        let path = Self::corpus_path(&self.data_dir, version);
+        let contents = std::fs::read_to_string(&path)
+            .with_context(|| format!("No persisted corpus for version {}", version))?;
+        let persisted: PersistedCorpus = serde_json::from_str(&contents)?;
+        Ok(persisted.tags)
+    }
+
+    /// Parse `value` into tags, compress them, and prefix the algorithm/version header.
+    pub fn encode(&mut self, value: &str) -> Result<Vec<u8>> {
+        let tags = CommaSeparatedParser::new().parse(value)?;
+        // `compress_tags`, not `compress_tags_with_corpus`: the latter unconditionally
+        // retrains `self.storage` from just this one value's handful of tags, discarding
+        // the shared corpus `refresh_corpus`/`new` already trained it from. Each backend's
+        // `compress` embeds its model in the payload when `self.storage` is still
+        // untrained, so this stays correct even before the first `refresh_corpus`.
+        let compressed = self.storage.compress_tags(&tags)?;
+
+        for tag in &tags {
+            self.corpus.insert(tag.clone());
+        }
+
+        let mut encoded = Vec::with_capacity(compressed.len() + HEADER_LEN);
+        encoded.push(self.algorithm.header_byte());
+        encoded.push(self.version);
+        encoded.extend(&compressed);
+
+        self.metrics.entries += 1;
+        self.metrics.original_bytes += value.len() as u64;
+        self.metrics.compressed_bytes += compressed.len() as u64;
+        self.metrics.stored_bytes += encoded.len() as u64;
+
+        Ok(encoded)
+    }
+
+    /// Reverse `encode`, decoding against whichever corpus version produced `data`.
+    pub fn decode(&self, data: &[u8]) -> Result<String> {
+        if data.len() < HEADER_LEN {
+            bail!("Value too short to contain a compression header");
+        }
+        let algorithm = ValueAlgorithm::from_header_byte(data[0])?;
+        let version = data[1];
+        let payload = &data[HEADER_LEN..];
+
+        let storage = if version == self.version && algorithm == self.algorithm {
+            None
+        } else {
+            let tags = self.load_version(version)?;
+            let mut storage = algorithm.new_storage();
+            if !tags.is_empty() {
+                storage.build_corpus(&tags)?;
+            }
+            Some(storage)
+        };
+
+        let tags = match &storage {
+            Some(storage) => storage.decompress_tags(payload)?,
+            None => self.storage.decompress_tags(payload)?,
+        };
+
+        Ok(tags.join(","))
+    }
+
+    /// Persist the accumulated corpus under a new version and retrain the active compressor.
+    pub fn refresh_corpus(&mut self) -> Result<()> {
+        let tags: Vec<String> = self.corpus.iter().cloned().collect();
+        let next_version = self.version.wrapping_add(1);
+
+        let persisted = PersistedCorpus {
+            version: next_version,
+            tags: tags.clone(),
+        };
+        let path = Self::corpus_path(&self.data_dir, next_version);
+        let contents = serde_json::to_string(&persisted)?;
+        std::fs::write(&path, contents)
+            .with_context(|| format!("Failed to persist corpus: {}", path.display()))?;
+
+        let mut storage = self.algorithm.new_storage();
+        if !tags.is_empty() {
+            storage.build_corpus(&tags)?;
+        }
+        self.storage = storage;
+        self.version = next_version;
+
+        Ok(())
+    }
+
+    pub fn metrics(&self) -> ValueCompressionMetrics {
+        self.metrics.clone()
+    }
+
+    pub fn algorithm_name(&self) -> &'static str {
+        self.storage.algorithm_name()
+    }
+
+    pub fn algorithm(&self) -> ValueAlgorithm {
+        self.algorithm
+    }
+
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    static TEST_DIR_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+    /// A fresh, unique scratch `data_dir` per test, since the repo has no `tempfile`
+    /// dependency to lean on.
+    fn test_data_dir() -> PathBuf {
+        let n = TEST_DIR_COUNTER.fetch_add(1, Ordering::Relaxed);
+        let dir = std::env::temp_dir().join(format!(
+            "mappy-value-compression-test-{}-{}",
+            std::process::id(),
+            n
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_encode_decode_round_trip_before_any_refresh() {
+        // No corpus has been persisted or refreshed yet, so `self.storage` is untrained;
+        // `compress_tags` must still work by embedding a transient model in the payload.
+        let mut state =
+            ValueCompressionState::new(ValueAlgorithm::Huffman, test_data_dir().to_str().unwrap())
+                .unwrap();
+
+        let encoded = state.encode("a,b,a").unwrap();
+        let decoded = state.decode(&encoded).unwrap();
+
+        assert_eq!(decoded, "a,b,a");
+    }
+
+    #[test]
+    fn test_encode_decode_round_trip_after_refresh_corpus() {
+        let mut state =
+            ValueCompressionState::new(ValueAlgorithm::Huffman, test_data_dir().to_str().unwrap())
+                .unwrap();
+
+        state.encode("a,b").unwrap();
+        state.refresh_corpus().unwrap();
+        assert_eq!(state.version(), 1);
+
+        let encoded = state.encode("a,b,a").unwrap();
+        let decoded = state.decode(&encoded).unwrap();
+
+        assert_eq!(decoded, "a,b,a");
+    }
+
+    #[test]
+    fn test_encode_relies_on_shared_corpus_instead_of_retraining_per_call() {
+        // Once `refresh_corpus` has trained `self.storage` from the shared corpus,
+        // `encode` must not silently retrain it from just the new value's tags (the
+        // `compress_tags_with_corpus` bug this test guards against) — a tag the shared
+        // corpus never saw should surface as an error instead of being silently accepted
+        // into a throwaway one-off model.
+        let mut state =
+            ValueCompressionState::new(ValueAlgorithm::Huffman, test_data_dir().to_str().unwrap())
+                .unwrap();
+
+        state.encode("a,b").unwrap();
+        state.refresh_corpus().unwrap();
+
+        let err = state.encode("a,b,unseen-tag").unwrap_err();
+        assert!(err.to_string().contains("unseen-tag") || err.to_string().contains("dictionary"));
+    }
+
+    #[test]
+    fn test_refresh_corpus_persists_new_version_file() {
+        let data_dir = test_data_dir();
+        let mut state =
+            ValueCompressionState::new(ValueAlgorithm::Huffman, data_dir.to_str().unwrap())
+                .unwrap();
+
+        state.encode("a,b").unwrap();
+        state.refresh_corpus().unwrap();
+
+        assert!(ValueCompressionState::corpus_path(&data_dir, 1).exists());
+    }
+
+    #[test]
+    fn test_metrics_track_entries_and_byte_counts() {
+        let mut state =
+            ValueCompressionState::new(ValueAlgorithm::Huffman, test_data_dir().to_str().unwrap())
+                .unwrap();
+
+        state.encode("a,b,a").unwrap();
+        state.encode("c").unwrap();
+
+        let metrics = state.metrics();
+        assert_eq!(metrics.entries, 2);
+        assert!(metrics.original_bytes > 0);
+        assert!(metrics.stored_bytes > 0);
+    }
+}
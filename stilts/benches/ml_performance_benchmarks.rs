@@ -35,17 +35,18 @@ fn benchmark_similarity_search_approximate(c: &mut Criterion) {
     let tag_sets = generate_benchmark_data();
     let query_tags = tag_sets[0].clone();
 
+    // Use criterion's async_tokio executor so only the awaited mappy work is timed,
+    // instead of folding tokio runtime/scheduling overhead into every iteration via
+    // a per-iteration block_on.
     c.bench_function("similarity_search_approximate", |b| {
-        b.iter(|| {
-            rt.block_on(async {
-                let result = MLBenchmarkRunner::benchmark_similarity_search(
-                    black_box(&tag_sets),
-                    black_box(&query_tags),
-                    10,
-                )
-                .await;
-                black_box(result)
-            })
+        b.to_async(&rt).iter(|| async {
+            let result = MLBenchmarkRunner::benchmark_similarity_search(
+                black_box(&tag_sets),
+                black_box(&query_tags),
+                10,
+            )
+            .await;
+            black_box(result)
         })
     });
 }
@@ -68,11 +69,9 @@ fn benchmark_clustering_approximate(c: &mut Criterion) {
     let tag_sets = generate_benchmark_data();
 
     c.bench_function("clustering_approximate", |b| {
-        b.iter(|| {
-            rt.block_on(async {
-                let result = MLBenchmarkRunner::benchmark_clustering(black_box(&tag_sets), 5).await;
-                black_box(result)
-            })
+        b.to_async(&rt).iter(|| async {
+            let result = MLBenchmarkRunner::benchmark_clustering(black_box(&tag_sets), 5).await;
+            black_box(result)
         })
     });
 }
@@ -98,15 +97,13 @@ fn benchmark_embeddings_approximate(c: &mut Criterion) {
     let query_tags = tag_sets[0].clone();
 
     c.bench_function("embeddings_approximate", |b| {
-        b.iter(|| {
-            rt.block_on(async {
-                let result = MLBenchmarkRunner::benchmark_embeddings(
-                    black_box(&tag_sets),
-                    black_box(&query_tags),
-                )
-                .await;
-                black_box(result)
-            })
+        b.to_async(&rt).iter(|| async {
+            let result = MLBenchmarkRunner::benchmark_embeddings(
+                black_box(&tag_sets),
+                black_box(&query_tags),
+            )
+            .await;
+            black_box(result)
         })
     });
 }
@@ -125,10 +122,8 @@ fn benchmark_ml_tasks_comparison(c: &mut Criterion) {
     });
 
     group.bench_function("similarity_approximate", |b| {
-        b.iter(|| {
-            rt.block_on(async {
-                MLBenchmarkRunner::benchmark_similarity_search(&tag_sets, &query_tags, 10).await
-            })
+        b.to_async(&rt).iter(|| async {
+            MLBenchmarkRunner::benchmark_similarity_search(&tag_sets, &query_tags, 10).await
         })
     });
 
@@ -138,9 +133,8 @@ fn benchmark_ml_tasks_comparison(c: &mut Criterion) {
     });
 
     group.bench_function("clustering_approximate", |b| {
-        b.iter(|| {
-            rt.block_on(async { MLBenchmarkRunner::benchmark_clustering(&tag_sets, 5).await })
-        })
+        b.to_async(&rt)
+            .iter(|| async { MLBenchmarkRunner::benchmark_clustering(&tag_sets, 5).await })
     });
 
     group.finish();
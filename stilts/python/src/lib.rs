@@ -7,6 +7,10 @@ use stilts::benchmark::{BenchmarkRunner, ComparisonRunner};
 use stilts::plotting::ReportGenerator;
 use serde_json;
 
+fn py_value_error(e: impl std::fmt::Display) -> PyErr {
+    PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string())
+}
+
 #[pyclass]
 pub struct StiltsCompressor {
     huffman: HuffmanCompressor,
@@ -24,27 +28,57 @@ impl StiltsCompressor {
             dictionary: DictionaryCompressor::new(),
         }
     }
-    
+
+    /// Train `algorithm`'s backend ("huffman", "arithmetic", or "dictionary") on `tags`, so
+    /// the resulting model can be reused by many later `compress`/`save` calls instead of
+    /// being rebuilt from scratch every time.
+    fn train(&mut self, tags: Vec<String>, algorithm: String) -> PyResult<()> {
+        match algorithm.as_str() {
+            "huffman" => self.huffman.build_from_corpus(&tags),
+            "arithmetic" => self.arithmetic.build_from_corpus(&tags),
+            "dictionary" => self.dictionary.build_from_corpus(&tags),
+            _ => return Err(py_value_error("Unknown algorithm")),
+        }
+        .map_err(py_value_error)
+    }
+
+    /// Serialize `algorithm`'s trained model, so it can be written to disk and restored in
+    /// another process via `load` instead of retraining on the original corpus.
+    fn save(&self, algorithm: String) -> PyResult<Vec<u8>> {
+        let compressor: &dyn Compressor = match algorithm.as_str() {
+            "huffman" => &self.huffman,
+            "arithmetic" => &self.arithmetic,
+            "dictionary" => &self.dictionary,
+            _ => return Err(py_value_error("Unknown algorithm")),
+        };
+        compressor.save_model().map_err(py_value_error)
+    }
+
+    /// Restore `algorithm`'s model from bytes produced by a prior `save` call.
+    fn load(&mut self, algorithm: String, data: Vec<u8>) -> PyResult<()> {
+        let compressor: &mut dyn Compressor = match algorithm.as_str() {
+            "huffman" => &mut self.huffman,
+            "arithmetic" => &mut self.arithmetic,
+            "dictionary" => &mut self.dictionary,
+            _ => return Err(py_value_error("Unknown algorithm")),
+        };
+        compressor.load_model(&data).map_err(py_value_error)
+    }
+
+    /// Compress `tags` with `algorithm`'s backend, training it on `tags` first only if it
+    /// has not already been trained (via `train` or `load`) — so a model trained once is
+    /// reused across many `compress` calls instead of being rebuilt every time.
     fn compress(&mut self, tags: Vec<String>, algorithm: String) -> PyResult<Vec<u8>> {
         let compressor: &dyn Compressor = match algorithm.as_str() {
-            "huffman" => {
-                self.huffman.build_from_corpus(&tags).map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-                &self.huffman
-            },
-            "arithmetic" => {
-                self.arithmetic.build_from_corpus(&tags).map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-                &self.arithmetic
-            },
-            "dictionary" => {
-                self.dictionary.build_from_corpus(&tags).map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-                &self.dictionary
-            },
-            _ => return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>("Unknown algorithm")),
+            "huffman" => &self.huffman,
+            "arithmetic" => &self.arithmetic,
+            "dictionary" => &self.dictionary,
+            _ => return Err(py_value_error("Unknown algorithm")),
         };
-        
-        compressor.compress(&tags).map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))
+
+        compressor.compress(&tags).map_err(py_value_error)
     }
-    
+
     fn decompress(&self, data: Vec<u8>, algorithm: String) -> PyResult<Vec<String>> {
         let compressor: &dyn Compressor = match algorithm.as_str() {
             "huffman" => &self.huffman,
@@ -0,0 +1,106 @@
+//! Baseline regression detection for compression-speed benchmarks.
+//!
+//! `save_baseline` snapshots a `benchmark_all`-style result set to disk; `compare_to_baseline`
+//! later checks a new result set against that snapshot and classifies each algorithm's
+//! compression time as improved, regressed, or unchanged based on whether the new
+//! bootstrapped confidence interval falls entirely below, entirely above, or overlaps the
+//! stored one.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json;
+use crate::benchmark::metrics::BenchmarkMetrics;
+
+/// How an algorithm's compression time changed relative to a stored baseline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RegressionVerdict {
+    /// The new confidence interval is entirely below the baseline's (faster).
+    Improved,
+    /// The new confidence interval is entirely above the baseline's (slower).
+    Regressed,
+    /// The confidence intervals overlap — no statistically significant change.
+    Unchanged,
+}
+
+/// Persist `metrics` to `path` as JSON, for a later `compare_to_baseline` call.
+pub fn save_baseline(metrics: &[BenchmarkMetrics], path: &str) -> Result<()> {
+    let json = serde_json::to_string_pretty(metrics)
+        .context("failed to serialize baseline metrics")?;
+    std::fs::write(path, json).with_context(|| format!("failed to write baseline to {path}"))
+}
+
+/// Compare `metrics` against the baseline previously saved to `path`, classifying each
+/// algorithm present in both as `Improved`, `Regressed`, or `Unchanged`. Algorithms missing a
+/// `compression_time_stats` sample (e.g. results from `benchmark_deterministic`) or missing
+/// from the baseline are skipped rather than compared.
+pub fn compare_to_baseline(
+    metrics: &[BenchmarkMetrics],
+    path: &str,
+) -> Result<Vec<(String, RegressionVerdict)>> {
+    let baseline_json = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read baseline from {path}"))?;
+    let baseline: Vec<BenchmarkMetrics> = serde_json::from_str(&baseline_json)
+        .context("failed to parse baseline metrics")?;
+
+    let mut verdicts = Vec::new();
+    for metric in metrics {
+        let Some(new_stats) = &metric.stats.compression_time_stats else {
+            continue;
+        };
+        let Some(baseline_metric) = baseline.iter().find(|b| b.algorithm == metric.algorithm) else {
+            continue;
+        };
+        let Some(old_stats) = &baseline_metric.stats.compression_time_stats else {
+            continue;
+        };
+
+        let (new_lo, new_hi) = new_stats.confidence_interval_95;
+        let (old_lo, old_hi) = old_stats.confidence_interval_95;
+
+        let verdict = if new_hi < old_lo {
+            RegressionVerdict::Improved
+        } else if new_lo > old_hi {
+            RegressionVerdict::Regressed
+        } else {
+            RegressionVerdict::Unchanged
+        };
+
+        verdicts.push((metric.algorithm.clone(), verdict));
+    }
+
+    Ok(verdicts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::benchmark::metrics::CompressionStats;
+    use crate::benchmark::stats::compute_sample_stats;
+
+    fn metrics_with_times(algorithm: &str, times: &[f64]) -> BenchmarkMetrics {
+        let mut stats = CompressionStats::new(100, 50, times.iter().sum::<f64>() / times.len() as f64, 1.0, 0);
+        stats.compression_time_stats = Some(compute_sample_stats(times));
+        BenchmarkMetrics {
+            algorithm: algorithm.to_string(),
+            stats,
+            memory_usage_bytes: 50,
+            complexity: None,
+        }
+    }
+
+    #[test]
+    fn test_compare_to_baseline_round_trips_and_detects_improvement() {
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("baseline-test-{:?}.json", std::thread::current().id()));
+        let path = path.to_str().unwrap();
+
+        let baseline = vec![metrics_with_times("huffman", &[10.0, 10.1, 9.9, 10.2, 9.8])];
+        save_baseline(&baseline, path).unwrap();
+
+        let improved = vec![metrics_with_times("huffman", &[1.0, 1.1, 0.9, 1.2, 0.8])];
+        let verdicts = compare_to_baseline(&improved, path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(verdicts, vec![("huffman".to_string(), RegressionVerdict::Improved)]);
+    }
+}
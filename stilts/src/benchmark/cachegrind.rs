@@ -0,0 +1,137 @@
+//! Deterministic instruction-count benchmarking under Cachegrind.
+//!
+//! `BenchmarkRunner::benchmark` times a compressor with `Instant`, which is noisy in CI
+//! containers that share a host with other tenants. `BenchmarkRunner::benchmark_deterministic`
+//! instead re-execs the current process under `valgrind --tool=cachegrind` and measures the
+//! exact number of instructions retired, which is reproducible bit-for-bit across runs and
+//! machines. This only works when the `cachegrind` feature is enabled and Valgrind is on
+//! `PATH`; otherwise `running_under_valgrind` and `reexec_under_cachegrind` report themselves
+//! unavailable so callers can fall back to wall-clock timing.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+/// Retired instruction and cache-miss counts for a run, as reported by Cachegrind's summary
+/// line. `None` on a `CompressionStats` means the stats came from ordinary wall-clock timing
+/// instead.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct InstructionCounts {
+    pub instructions: u64,
+    pub l1_misses: u64,
+    pub ll_misses: u64,
+}
+
+/// True if this process is already running under Valgrind (any tool), checked through the
+/// client-request mechanism rather than an environment variable, so it can't be spoofed by a
+/// stray `VALGRIND`-looking variable in the child's environment.
+#[cfg(feature = "cachegrind")]
+pub fn running_under_valgrind() -> bool {
+    crabgrind::run_mode() != crabgrind::RunMode::Native
+}
+
+#[cfg(not(feature = "cachegrind"))]
+pub fn running_under_valgrind() -> bool {
+    false
+}
+
+/// Re-exec the current process, with its original arguments, under
+/// `valgrind --tool=cachegrind`, writing the summary to `cachegrind_out_path`. Blocks until the
+/// child exits, so `cachegrind_out_path` is complete and ready to parse once this returns `Ok`.
+/// Returns an error rather than panicking when Valgrind can't be launched (not installed, not
+/// on `PATH`, or the `cachegrind` feature is disabled) — callers should fall back to wall-clock
+/// timing in that case.
+#[cfg(feature = "cachegrind")]
+pub fn reexec_under_cachegrind(cachegrind_out_path: &str) -> Result<()> {
+    let exe = std::env::current_exe().context("could not determine current executable")?;
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let status = Command::new("valgrind")
+        .arg("--tool=cachegrind")
+        .arg(format!("--cachegrind-out-file={cachegrind_out_path}"))
+        .arg(&exe)
+        .args(&args)
+        .status()
+        .context("failed to launch valgrind; is it installed and on PATH?")?;
+
+    if !status.success() {
+        anyhow::bail!("valgrind exited with {status}");
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "cachegrind"))]
+pub fn reexec_under_cachegrind(_cachegrind_out_path: &str) -> Result<()> {
+    anyhow::bail!("the `cachegrind` feature is not enabled")
+}
+
+/// Parse Cachegrind's `events:` header and final `summary:` line for the instruction and
+/// cache-miss totals over the whole run.
+pub fn parse_cachegrind_output(path: &str) -> Result<InstructionCounts> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read cachegrind output at {path}"))?;
+
+    let events: Vec<&str> = contents
+        .lines()
+        .find(|line| line.starts_with("events:"))
+        .context("cachegrind output missing events line")?
+        .trim_start_matches("events:")
+        .split_whitespace()
+        .collect();
+
+    let values: Vec<u64> = contents
+        .lines()
+        .find(|line| line.starts_with("summary:"))
+        .context("cachegrind output missing summary line")?
+        .trim_start_matches("summary:")
+        .split_whitespace()
+        .map(|v| v.parse().unwrap_or(0))
+        .collect();
+
+    let field = |name: &str| -> u64 {
+        events
+            .iter()
+            .position(|&event| event == name)
+            .and_then(|i| values.get(i))
+            .copied()
+            .unwrap_or(0)
+    };
+
+    Ok(InstructionCounts {
+        instructions: field("Ir"),
+        l1_misses: field("I1mr") + field("D1mr") + field("D1mw"),
+        ll_misses: field("ILmr") + field("DLmr") + field("DLmw"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cachegrind_output_reads_summary_counts() {
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("cachegrind-test-{:?}.out", std::thread::current().id()));
+        std::fs::write(
+            &path,
+            "version: 1\n\
+             creator: cachegrind-3.0\n\
+             events: Ir I1mr ILmr Dr D1mr DLmr Dw D1mw DLmw\n\
+             summary: 1000 10 2 500 5 1 200 4 1\n",
+        )
+        .unwrap();
+
+        let counts = parse_cachegrind_output(path.to_str().unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(counts.instructions, 1000);
+        assert_eq!(counts.l1_misses, 10 + 5 + 4);
+        assert_eq!(counts.ll_misses, 2 + 1 + 1);
+    }
+
+    #[test]
+    #[cfg(not(feature = "cachegrind"))]
+    fn test_running_under_valgrind_is_false_without_the_feature() {
+        assert!(!running_under_valgrind());
+    }
+}
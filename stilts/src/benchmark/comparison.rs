@@ -1,12 +1,72 @@
 //! Comparison with external compression libraries
 
 use std::time::Instant;
-use anyhow::Result;
+use anyhow::{Context, Result};
 use flate2::Compression;
 use flate2::write::{ZlibEncoder, GzEncoder};
 use flate2::read::{ZlibDecoder, GzDecoder};
 use std::io::{Write, Read};
 use crate::benchmark::metrics::{BenchmarkMetrics, CompressionStats};
+use crate::compression::{Compressor, DictionaryCompressor, HuffmanCompressor};
+
+/// Which backend a `ComparisonRunner` benchmark should exercise. Lets callers select
+/// a subset instead of always running the full `compare_all` sweep.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressionMethod {
+    Dict,
+    Deflate,
+    Gzip,
+    Lz4,
+    Zstd,
+    Snappy,
+    Brotli,
+    MappyDictionary,
+    MappyHuffman,
+}
+
+impl CompressionMethod {
+    /// Every backend `compare_all` runs, in the order results are reported.
+    pub fn all() -> Vec<Self> {
+        vec![
+            Self::Dict,
+            Self::Deflate,
+            Self::Gzip,
+            Self::Lz4,
+            Self::Zstd,
+            Self::Snappy,
+            Self::Brotli,
+            Self::MappyDictionary,
+            Self::MappyHuffman,
+        ]
+    }
+
+    fn run(self, data: &[u8], iterations: usize) -> Result<BenchmarkMetrics> {
+        match self {
+            Self::Dict => ComparisonRunner::benchmark_dict(data, iterations),
+            Self::Deflate => ComparisonRunner::benchmark_zlib(data, iterations),
+            Self::Gzip => ComparisonRunner::benchmark_gzip(data, iterations),
+            Self::Lz4 => ComparisonRunner::benchmark_lz4(data, iterations),
+            Self::Zstd => ComparisonRunner::benchmark_zstd(data, iterations),
+            Self::Snappy => ComparisonRunner::benchmark_snappy(data, iterations),
+            Self::Brotli => ComparisonRunner::benchmark_brotli(data, iterations),
+            Self::MappyDictionary => ComparisonRunner::benchmark_mappy_dictionary(data, iterations),
+            Self::MappyHuffman => ComparisonRunner::benchmark_mappy_huffman(data, iterations),
+        }
+    }
+}
+
+/// Treat each byte as its own "tag" (its decimal value as a string) so mappy's
+/// tag-oriented compressors can be benchmarked against the same raw byte buffers
+/// as the general-purpose codecs, with an exact, reversible round trip.
+fn bytes_to_tags(data: &[u8]) -> Vec<String> {
+    data.iter().map(|b| b.to_string()).collect()
+}
+
+fn tags_to_bytes(tags: &[String]) -> Result<Vec<u8>> {
+    tags.iter()
+        .map(|s| s.parse::<u8>().with_context(|| format!("Not a byte tag: {}", s)))
+        .collect()
+}
 
 /// Comparison runner for external libraries
 pub struct ComparisonRunner;
@@ -55,6 +115,7 @@ impl ComparisonRunner {
             algorithm: "zlib".to_string(),
             stats,
             memory_usage_bytes: compressed_size,
+            complexity: None,
         })
     }
     
@@ -101,6 +162,7 @@ impl ComparisonRunner {
             algorithm: "gzip".to_string(),
             stats,
             memory_usage_bytes: compressed_size,
+            complexity: None,
         })
     }
     
@@ -141,6 +203,7 @@ impl ComparisonRunner {
             algorithm: "lz4".to_string(),
             stats,
             memory_usage_bytes: compressed_size,
+            complexity: None,
         })
     }
     
@@ -161,19 +224,241 @@ impl ComparisonRunner {
             algorithm: "dict".to_string(),
             stats,
             memory_usage_bytes: original_size,
+            complexity: None,
         })
     }
     
-    /// Compare all external libraries
+    /// Benchmark zstd compression
+    pub fn benchmark_zstd(data: &[u8], iterations: usize) -> Result<BenchmarkMetrics> {
+        let original_size = data.len();
+
+        // Warmup
+        for _ in 0..3 {
+            let _ = zstd::stream::encode_all(data, 0)?;
+        }
+
+        // Benchmark compression
+        let start = Instant::now();
+        let mut compressed_data = Vec::new();
+        for _ in 0..iterations {
+            compressed_data = zstd::stream::encode_all(data, 0)?;
+        }
+        let compression_time = start.elapsed().as_secs_f64() * 1000.0 / iterations as f64;
+
+        // Benchmark decompression
+        let start = Instant::now();
+        for _ in 0..iterations {
+            let _ = zstd::stream::decode_all(&compressed_data[..])?;
+        }
+        let decompression_time = start.elapsed().as_secs_f64() * 1000.0 / iterations as f64;
+
+        let compressed_size = compressed_data.len();
+        let stats = CompressionStats::new(
+            original_size,
+            compressed_size,
+            compression_time,
+            decompression_time,
+            0,
+        );
+
+        Ok(BenchmarkMetrics {
+            algorithm: "zstd".to_string(),
+            stats,
+            memory_usage_bytes: compressed_size,
+            complexity: None,
+        })
+    }
+
+    /// Benchmark snappy compression
+    pub fn benchmark_snappy(data: &[u8], iterations: usize) -> Result<BenchmarkMetrics> {
+        let original_size = data.len();
+        let mut encoder = snap::raw::Encoder::new();
+
+        // Warmup
+        for _ in 0..3 {
+            let _ = encoder.compress_vec(data)?;
+        }
+
+        // Benchmark compression
+        let start = Instant::now();
+        let mut compressed_data = Vec::new();
+        for _ in 0..iterations {
+            compressed_data = encoder.compress_vec(data)?;
+        }
+        let compression_time = start.elapsed().as_secs_f64() * 1000.0 / iterations as f64;
+
+        // Benchmark decompression
+        let mut decoder = snap::raw::Decoder::new();
+        let start = Instant::now();
+        for _ in 0..iterations {
+            let _ = decoder.decompress_vec(&compressed_data)?;
+        }
+        let decompression_time = start.elapsed().as_secs_f64() * 1000.0 / iterations as f64;
+
+        let compressed_size = compressed_data.len();
+        let stats = CompressionStats::new(
+            original_size,
+            compressed_size,
+            compression_time,
+            decompression_time,
+            0,
+        );
+
+        Ok(BenchmarkMetrics {
+            algorithm: "snappy".to_string(),
+            stats,
+            memory_usage_bytes: compressed_size,
+            complexity: None,
+        })
+    }
+
+    /// Benchmark brotli compression
+    pub fn benchmark_brotli(data: &[u8], iterations: usize) -> Result<BenchmarkMetrics> {
+        let original_size = data.len();
+        let params = brotli::enc::BrotliEncoderParams::default();
+
+        let compress_once = |d: &[u8]| -> Result<Vec<u8>> {
+            let mut out = Vec::new();
+            let mut reader = &d[..];
+            brotli::BrotliCompress(&mut reader, &mut out, &params)?;
+            Ok(out)
+        };
+
+        // Warmup
+        for _ in 0..3 {
+            let _ = compress_once(data)?;
+        }
+
+        // Benchmark compression
+        let start = Instant::now();
+        let mut compressed_data = Vec::new();
+        for _ in 0..iterations {
+            compressed_data = compress_once(data)?;
+        }
+        let compression_time = start.elapsed().as_secs_f64() * 1000.0 / iterations as f64;
+
+        // Benchmark decompression
+        let start = Instant::now();
+        for _ in 0..iterations {
+            let mut decompressed = Vec::new();
+            let mut reader = &compressed_data[..];
+            brotli::BrotliDecompress(&mut reader, &mut decompressed)?;
+        }
+        let decompression_time = start.elapsed().as_secs_f64() * 1000.0 / iterations as f64;
+
+        let compressed_size = compressed_data.len();
+        let stats = CompressionStats::new(
+            original_size,
+            compressed_size,
+            compression_time,
+            decompression_time,
+            0,
+        );
+
+        Ok(BenchmarkMetrics {
+            algorithm: "brotli".to_string(),
+            stats,
+            memory_usage_bytes: compressed_size,
+            complexity: None,
+        })
+    }
+
+    /// Benchmark mappy's own `DictionaryCompressor`, treating each byte as a tag
+    pub fn benchmark_mappy_dictionary(data: &[u8], iterations: usize) -> Result<BenchmarkMetrics> {
+        let original_size = data.len();
+        let tags = bytes_to_tags(data);
+
+        let mut compressor = DictionaryCompressor::new();
+        compressor.build_from_corpus(&tags)?;
+
+        // Warmup
+        for _ in 0..3 {
+            let _ = compressor.compress(&tags)?;
+        }
+
+        let start = Instant::now();
+        let mut compressed_data = Vec::new();
+        for _ in 0..iterations {
+            compressed_data = compressor.compress(&tags)?;
+        }
+        let compression_time = start.elapsed().as_secs_f64() * 1000.0 / iterations as f64;
+
+        let start = Instant::now();
+        for _ in 0..iterations {
+            let decoded = compressor.decompress(&compressed_data)?;
+            let _ = tags_to_bytes(&decoded)?;
+        }
+        let decompression_time = start.elapsed().as_secs_f64() * 1000.0 / iterations as f64;
+
+        let compressed_size = compressed_data.len();
+        let stats = CompressionStats::new(
+            original_size,
+            compressed_size,
+            compression_time,
+            decompression_time,
+            0,
+        );
+
+        Ok(BenchmarkMetrics {
+            algorithm: "mappy-dictionary".to_string(),
+            stats,
+            memory_usage_bytes: compressed_size,
+            complexity: None,
+        })
+    }
+
+    /// Benchmark mappy's own `HuffmanCompressor`, treating each byte as a tag
+    pub fn benchmark_mappy_huffman(data: &[u8], iterations: usize) -> Result<BenchmarkMetrics> {
+        let original_size = data.len();
+        let tags = bytes_to_tags(data);
+
+        let mut compressor = HuffmanCompressor::new();
+        compressor.build_from_corpus(&tags)?;
+
+        // Warmup
+        for _ in 0..3 {
+            let _ = compressor.compress(&tags)?;
+        }
+
+        let start = Instant::now();
+        let mut compressed_data = Vec::new();
+        for _ in 0..iterations {
+            compressed_data = compressor.compress(&tags)?;
+        }
+        let compression_time = start.elapsed().as_secs_f64() * 1000.0 / iterations as f64;
+
+        let start = Instant::now();
+        for _ in 0..iterations {
+            let decoded = compressor.decompress(&compressed_data)?;
+            let _ = tags_to_bytes(&decoded)?;
+        }
+        let decompression_time = start.elapsed().as_secs_f64() * 1000.0 / iterations as f64;
+
+        let compressed_size = compressed_data.len();
+        let stats = CompressionStats::new(
+            original_size,
+            compressed_size,
+            compression_time,
+            decompression_time,
+            0,
+        );
+
+        Ok(BenchmarkMetrics {
+            algorithm: "mappy-huffman".to_string(),
+            stats,
+            memory_usage_bytes: compressed_size,
+            complexity: None,
+        })
+    }
+
+    /// Run an explicit subset of backends, in `methods` order.
+    pub fn compare(data: &[u8], iterations: usize, methods: &[CompressionMethod]) -> Result<Vec<BenchmarkMetrics>> {
+        methods.iter().map(|method| method.run(data, iterations)).collect()
+    }
+
+    /// Compare all external libraries and mappy's own compressors
     pub fn compare_all(data: &[u8], iterations: usize) -> Result<Vec<BenchmarkMetrics>> {
-        let mut results = Vec::new();
-        
-        results.push(Self::benchmark_dict(data, iterations)?);
-        results.push(Self::benchmark_zlib(data, iterations)?);
-        results.push(Self::benchmark_gzip(data, iterations)?);
-        results.push(Self::benchmark_lz4(data, iterations)?);
-        
-        Ok(results)
+        Self::compare(data, iterations, &CompressionMethod::all())
     }
 }
 
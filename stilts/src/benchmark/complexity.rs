@@ -0,0 +1,95 @@
+#![allow(clippy::cast_precision_loss)] // Acceptable for complexity-fitting calculations
+//! Empirical Big-O complexity estimation.
+//!
+//! `BenchmarkRunner::benchmark` reports a single (size, time) point, which can't distinguish
+//! a compressor that scales linearly in tag count from one that scales quadratically.
+//! `BenchmarkRunner::benchmark_complexity` instead times a compressor across a geometric sweep
+//! of corpus sizes and fits the resulting curve against a handful of candidate complexity
+//! models, picking whichever fits best.
+
+use serde::{Deserialize, Serialize};
+
+/// The winning complexity model for a `(corpus size, time)` curve, plus how well it fit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComplexityEstimate {
+    /// The big-O label of the best-fitting model, e.g. `"O(n)"`.
+    pub model: String,
+    /// The least-squares coefficient `c` such that `time ≈ c * basis(n)`.
+    pub coefficient: f64,
+    /// Root-mean-square residual of the fit, in the same units as the timing samples.
+    pub rms: f64,
+}
+
+/// A candidate complexity model: a label and the basis function `time` is assumed proportional
+/// to.
+struct CandidateModel {
+    label: &'static str,
+    basis: fn(f64) -> f64,
+}
+
+const CANDIDATE_MODELS: &[CandidateModel] = &[
+    CandidateModel { label: "O(1)", basis: |_n| 1.0 },
+    CandidateModel { label: "O(log n)", basis: f64::ln },
+    CandidateModel { label: "O(n)", basis: |n| n },
+    CandidateModel { label: "O(n log n)", basis: |n| n * n.ln() },
+    CandidateModel { label: "O(n^2)", basis: |n| n * n },
+];
+
+/// Fit `(corpus size, time)` samples against every `CANDIDATE_MODELS` entry via single-parameter
+/// least-squares regression through the origin (`time ≈ coefficient * basis(n)`), and return
+/// whichever model has the lowest residual RMS.
+///
+/// # Panics
+/// Panics if `samples` is empty — there is nothing to fit.
+pub fn fit_complexity(samples: &[(f64, f64)]) -> ComplexityEstimate {
+    assert!(!samples.is_empty(), "fit_complexity requires at least one sample");
+
+    CANDIDATE_MODELS
+        .iter()
+        .map(|candidate| {
+            let basis_values: Vec<f64> = samples.iter().map(|&(n, _)| (candidate.basis)(n)).collect();
+
+            let numerator: f64 = samples.iter().zip(&basis_values).map(|(&(_, t), &b)| t * b).sum();
+            let denominator: f64 = basis_values.iter().map(|b| b * b).sum();
+            let coefficient = if denominator > 0.0 { numerator / denominator } else { 0.0 };
+
+            let squared_error: f64 = samples
+                .iter()
+                .zip(&basis_values)
+                .map(|(&(_, t), &b)| (t - coefficient * b).powi(2))
+                .sum();
+            let rms = (squared_error / samples.len() as f64).sqrt();
+
+            ComplexityEstimate { model: candidate.label.to_string(), coefficient, rms }
+        })
+        .min_by(|a, b| a.rms.partial_cmp(&b.rms).expect("rms is never NaN"))
+        .expect("CANDIDATE_MODELS is non-empty")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_complexity_identifies_linear_growth() {
+        let samples: Vec<(f64, f64)> = (1..=10).map(|n| (n as f64, 2.0 * n as f64)).collect();
+        let estimate = fit_complexity(&samples);
+        assert_eq!(estimate.model, "O(n)");
+        assert!((estimate.coefficient - 2.0).abs() < 1e-6);
+        assert!(estimate.rms < 1e-6);
+    }
+
+    #[test]
+    fn test_fit_complexity_identifies_quadratic_growth() {
+        let samples: Vec<(f64, f64)> = (1..=10).map(|n| (n as f64, 0.5 * (n as f64).powi(2))).collect();
+        let estimate = fit_complexity(&samples);
+        assert_eq!(estimate.model, "O(n^2)");
+    }
+
+    #[test]
+    fn test_fit_complexity_identifies_constant_time() {
+        let samples: Vec<(f64, f64)> = (1..=10).map(|n| (n as f64, 5.0)).collect();
+        let estimate = fit_complexity(&samples);
+        assert_eq!(estimate.model, "O(1)");
+    }
+}
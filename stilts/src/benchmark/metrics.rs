@@ -2,6 +2,9 @@
 //! Performance metrics for compression benchmarks
 
 use serde::{Deserialize, Serialize};
+use crate::benchmark::cachegrind::InstructionCounts;
+use crate::benchmark::complexity::ComplexityEstimate;
+use crate::benchmark::stats::SampleStats;
 
 /// Compression statistics
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,6 +15,15 @@ pub struct CompressionStats {
     pub compression_speed_mbps: f64,
     pub decompression_speed_mbps: f64,
     pub dictionary_size: usize,
+    /// Deterministic instruction/cache-miss counts from `BenchmarkRunner::benchmark_deterministic`.
+    /// `None` for stats produced by ordinary wall-clock timing.
+    pub instruction_counts: Option<InstructionCounts>,
+    /// Per-iteration statistics (mean/median/stddev/confidence interval/outliers) for
+    /// compression time, from `BenchmarkRunner::benchmark`'s sample collection. `None` for
+    /// stats built directly via `CompressionStats::new` without per-iteration samples.
+    pub compression_time_stats: Option<SampleStats>,
+    /// As `compression_time_stats`, but for decompression time.
+    pub decompression_time_stats: Option<SampleStats>,
 }
 
 /// Benchmark metrics
@@ -20,6 +32,9 @@ pub struct BenchmarkMetrics {
     pub algorithm: String,
     pub stats: CompressionStats,
     pub memory_usage_bytes: usize,
+    /// The empirical Big-O fit from `BenchmarkRunner::benchmark_complexity`. `None` for
+    /// single-point benchmarks that never swept corpus sizes.
+    pub complexity: Option<ComplexityEstimate>,
 }
 
 impl CompressionStats {
@@ -55,6 +70,9 @@ impl CompressionStats {
             compression_speed_mbps,
             decompression_speed_mbps,
             dictionary_size,
+            instruction_counts: None,
+            compression_time_stats: None,
+            decompression_time_stats: None,
         }
     }
 }
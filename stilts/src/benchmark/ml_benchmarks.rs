@@ -9,7 +9,8 @@ use anyhow::Result;
 use crate::benchmark::ml_tasks::{
     TagSimilarity, TagClassifier, TagClustering, TagEmbedding, MLTaskResults,
 };
-use crate::mappy_integration::MappyTagStorage;
+use crate::compression::HuffmanCompressor;
+use crate::mappy_integration::{self, MappyTagStorage, TagBlockStore};
 
 #[cfg(feature = "mappy-integration")]
 use mappy_core::{Maplet, MergeOperator};
@@ -31,10 +32,129 @@ impl MergeOperator<Vec<u8>> for BytesOperator {
     }
 }
 
+/// Posting-list operator that appends rather than replaces: each `insert` for a
+/// band-bucket key contributes one more 4-byte little-endian item id, so repeated inserts
+/// for the same bucket accumulate the full candidate list instead of overwriting it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct PostingListOperator;
+
+#[cfg(feature = "mappy-integration")]
+impl MergeOperator<Vec<u8>> for PostingListOperator {
+    fn merge(&self, mut left: Vec<u8>, right: Vec<u8>) -> MapletResult<Vec<u8>> {
+        left.extend_from_slice(&right);
+        Ok(left)
+    }
+
+    fn identity(&self) -> Vec<u8> {
+        Vec::new()
+    }
+}
+
 /// Benchmark tag similarity search
 pub struct MLBenchmarkRunner;
 
 impl MLBenchmarkRunner {
+    /// Hex-encode a digest for use as a `Maplet` key (`Maplet` is keyed by `String`, not
+    /// raw bytes).
+    fn digest_key(digest: &mappy_integration::Digest) -> String {
+        digest.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    /// Compress and store each tag set under its content digest rather than its index, so
+    /// duplicate tag sets collapse to a single `Maplet` entry. Returns the per-index digest
+    /// (a lightweight `idx -> digest` map, since `Maplet` itself is keyed by digest) and the
+    /// dedup ratio (unique digests / total items; 1.0 means no duplicates were found).
+    #[cfg(feature = "mappy-integration")]
+    async fn store_content_addressed(
+        storage: &mut MappyTagStorage,
+        tag_sets: &[Vec<String>],
+        maplet: &Maplet<String, Vec<u8>, BytesOperator>,
+    ) -> Result<(Vec<mappy_integration::Digest>, f64)> {
+        let mut digests = Vec::with_capacity(tag_sets.len());
+        let mut unique: HashSet<mappy_integration::Digest> = HashSet::new();
+
+        for tags in tag_sets {
+            let compressed = storage.compress_tags(tags)?;
+            let digest = MappyTagStorage::digest(&compressed);
+            if unique.insert(digest) {
+                maplet.insert(Self::digest_key(&digest), compressed).await?;
+            }
+            digests.push(digest);
+        }
+
+        let dedup_ratio = unique.len() as f64 / tag_sets.len().max(1) as f64;
+        Ok((digests, dedup_ratio))
+    }
+
+    /// Resolve every digest produced by `store_content_addressed` back to tag sets in one
+    /// batch: fetch each *unique* digest's compressed bytes from `maplet`, then decompress
+    /// them via `MappyTagStorage::decompress_batch` (which dedups again internally), so a
+    /// payload shared by many items is decompressed only once.
+    #[cfg(feature = "mappy-integration")]
+    async fn decompress_batch_from_maplet(
+        storage: &MappyTagStorage,
+        maplet: &Maplet<String, Vec<u8>, BytesOperator>,
+        digests: &[mappy_integration::Digest],
+    ) -> Result<HashMap<mappy_integration::Digest, Vec<String>>> {
+        let mut entries = Vec::new();
+        let mut fetched: HashSet<mappy_integration::Digest> = HashSet::new();
+        for digest in digests {
+            if fetched.insert(*digest) {
+                if let Some(compressed) = maplet.query(&Self::digest_key(digest)).await {
+                    entries.push((*digest, compressed));
+                }
+            }
+        }
+        storage.decompress_batch(&entries)
+    }
+
+    /// Benchmark block-batched decode: pack `tag_sets` into `TagBlockStore` blocks of
+    /// `block_size` items and fetch `query_indices` in one batched call, against
+    /// decompressing each requested index individually via `MappyTagStorage::decompress_tags`.
+    /// When `query_indices` clusters several requests into the same block, this shows the
+    /// batched path's real amortized decode cost, since the other ML tasks in this module
+    /// assume already-decompressed input and would otherwise hide this cost entirely.
+    #[cfg(feature = "mappy-integration")]
+    pub async fn benchmark_block_batched_decode(
+        tag_sets: &[Vec<String>],
+        query_indices: &[usize],
+        block_size: usize,
+    ) -> Result<MLTaskResults> {
+        let mut storage = MappyTagStorage::with_huffman();
+        let all_tags: Vec<String> = tag_sets.iter().flatten().cloned().collect();
+        storage.build_corpus(&all_tags)?;
+
+        let per_item_compressed: Vec<Vec<u8>> = tag_sets
+            .iter()
+            .map(|tags| storage.compress_tags(tags))
+            .collect::<Result<_>>()?;
+
+        let start = Instant::now();
+        let mut per_item_results = Vec::with_capacity(query_indices.len());
+        for &idx in query_indices {
+            per_item_results.push(storage.decompress_tags(&per_item_compressed[idx])?);
+        }
+        let per_item_time = start.elapsed().as_secs_f64() * 1000.0;
+
+        let mut block_compressor = HuffmanCompressor::new();
+        block_compressor.build_from_corpus(&all_tags)?;
+        let block_store = TagBlockStore::build(tag_sets, Box::new(block_compressor), block_size)?;
+
+        let start = Instant::now();
+        let block_results = block_store.fetch_items(query_indices)?;
+        let block_time = start.elapsed().as_secs_f64() * 1000.0;
+
+        let accuracy = if per_item_results == block_results { 1.0 } else { 0.0 };
+
+        Ok(MLTaskResults::new(
+            "block_batched_decode".to_string(),
+            accuracy,
+            accuracy,
+            per_item_time,
+            block_time,
+        ))
+    }
+
     /// Generate test tag sets for ML tasks
     pub fn generate_ml_test_data(
         num_items: usize,
@@ -86,26 +206,25 @@ impl MLBenchmarkRunner {
             tag_sets.len() * 3, // Extra capacity to avoid overflow
             0.01, // 1% false positive rate
         )?;
-        
-        // Store all tag sets
-        for (idx, tags) in tag_sets.iter().enumerate() {
-            let compressed = storage.compress_tags(tags)?;
-            mappy_maplet.insert(format!("item_{}", idx), compressed).await?;
-        }
-        
-        // OPTIMIZATION: Pre-decompress all tag sets into cache (like classification)
-        // This moves mappy queries out of the hot path
+
+        // Content-addressed store: duplicate tag sets collapse to one `Maplet` entry.
+        let (digests, dedup_ratio) =
+            Self::store_content_addressed(&mut storage, tag_sets, &mappy_maplet).await?;
+
+        // OPTIMIZATION: Pre-decompress all tag sets into cache (like classification), in
+        // one batch call so a payload shared by duplicate tag sets is decompressed once.
+        // This moves mappy queries out of the hot path.
+        let decompressed_by_digest =
+            Self::decompress_batch_from_maplet(&storage, &mappy_maplet, &digests).await?;
         let mut cached_tag_sets: Vec<Vec<String>> = Vec::with_capacity(tag_sets.len());
         for (idx, original_tags) in tag_sets.iter().enumerate() {
-            if let Some(compressed) = mappy_maplet.query(&format!("item_{}", idx)).await {
-                let decompressed = storage.decompress_tags(&compressed)?;
-                cached_tag_sets.push(decompressed);
-            } else {
-                // Fallback: use original if query fails (shouldn't happen)
-                cached_tag_sets.push(original_tags.clone());
+            match decompressed_by_digest.get(&digests[idx]) {
+                Some(decompressed) => cached_tag_sets.push(decompressed.clone()),
+                // Fallback: use original if lookup fails (shouldn't happen)
+                None => cached_tag_sets.push(original_tags.clone()),
             }
         }
-        
+
         // Benchmark approximate similarity computation from cached data
         let start = Instant::now();
         let mut approximate_results = Vec::new();
@@ -134,9 +253,275 @@ impl MLBenchmarkRunner {
             approximate_accuracy,
             exact_time,
             approximate_time,
+        )
+        .with_dedup_ratio(dedup_ratio))
+    }
+
+    /// Serialize a MinHash signature to bytes (little-endian `u64`s) for storage in a
+    /// `Maplet<String, Vec<u8>, BytesOperator>`.
+    fn signature_to_bytes(signature: &[u64]) -> Vec<u8> {
+        signature.iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+
+    /// Inverse of `signature_to_bytes`.
+    fn signature_from_bytes(bytes: &[u8]) -> Vec<u64> {
+        bytes
+            .chunks_exact(8)
+            .map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap()))
+            .collect()
+    }
+
+    /// Benchmark similarity search using MinHash signatures instead of full tag sets, so
+    /// the hot path never reconstructs (decompresses) the original tag strings. Signatures
+    /// are stored directly in the `Maplet` via `BytesOperator`, mirroring how
+    /// `benchmark_similarity_search` stores Huffman-compressed bytes.
+    #[cfg(feature = "mappy-integration")]
+    pub async fn benchmark_similarity_search_minhash(
+        tag_sets: &[Vec<String>],
+        query_tags: &[String],
+        top_k: usize,
+    ) -> Result<MLTaskResults> {
+        // Exact: Direct tag set comparison
+        let start = Instant::now();
+        let exact_results = TagSimilarity::find_most_similar(query_tags, tag_sets, top_k);
+        let exact_time = start.elapsed().as_secs_f64() * 1000.0;
+
+        let signature_maplet = Maplet::<String, Vec<u8>, BytesOperator>::new(
+            tag_sets.len() * 3,
+            0.01,
+        )?;
+
+        for (idx, tags) in tag_sets.iter().enumerate() {
+            let signature = MappyTagStorage::minhash_signature(tags);
+            signature_maplet
+                .insert(format!("item_{}", idx), Self::signature_to_bytes(&signature))
+                .await?;
+        }
+
+        let query_signature = MappyTagStorage::minhash_signature(query_tags);
+
+        // Pre-fetch every signature so the ranking pass below has no mappy queries in its
+        // hot path, mirroring the pre-decompress caching `benchmark_similarity_search` does.
+        let mut cached_signatures: Vec<Vec<u64>> = Vec::with_capacity(tag_sets.len());
+        for idx in 0..tag_sets.len() {
+            let bytes = signature_maplet
+                .query(&format!("item_{}", idx))
+                .await
+                .unwrap_or_default();
+            cached_signatures.push(Self::signature_from_bytes(&bytes));
+        }
+
+        let start = Instant::now();
+        let mut approximate_results: Vec<(usize, f64)> = cached_signatures
+            .iter()
+            .enumerate()
+            .map(|(idx, sig)| (idx, mappy_integration::approx_jaccard(&query_signature, sig)))
+            .collect();
+        approximate_results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+        approximate_results.truncate(top_k);
+        let approximate_time = start.elapsed().as_secs_f64() * 1000.0;
+
+        let exact_set: HashSet<usize> = exact_results.iter().map(|(idx, _)| *idx).collect();
+        let approximate_set: HashSet<usize> =
+            approximate_results.iter().map(|(idx, _)| *idx).collect();
+        let intersection = exact_set.intersection(&approximate_set).count();
+        let accuracy = intersection as f64 / top_k as f64;
+
+        // Mean absolute error between the MinHash estimate and the exact Jaccard value,
+        // over every candidate (not just the top-k), to characterize estimator quality
+        // independent of how it affects top-k ranking.
+        let exact_jaccards: Vec<f64> = tag_sets
+            .iter()
+            .map(|tags| TagSimilarity::jaccard_similarity(query_tags, tags))
+            .collect();
+        let total_error: f64 = cached_signatures
+            .iter()
+            .zip(exact_jaccards.iter())
+            .map(|(sig, &exact)| (mappy_integration::approx_jaccard(&query_signature, sig) - exact).abs())
+            .sum();
+        let mean_error = total_error / tag_sets.len().max(1) as f64;
+
+        Ok(MLTaskResults::new(
+            "similarity_search_minhash".to_string(),
+            accuracy,
+            accuracy,
+            exact_time,
+            approximate_time,
+        )
+        .with_minhash_jaccard_error(mean_error))
+    }
+
+    /// Band an LSH key: each band hashes its `r` MinHash rows down to a single bucket id,
+    /// so the Maplet key only needs the band index plus that bucket.
+    fn lsh_band_key(band_idx: usize, band_rows: &[u64]) -> String {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        band_rows.hash(&mut hasher);
+        format!("lsh_band{}_{}", band_idx, hasher.finish())
+    }
+
+    /// Benchmark similarity search using LSH banding for candidate generation: instead of
+    /// ranking every item against the query (as `benchmark_similarity_search_minhash`
+    /// does), only items that collide with the query in at least one band are ranked. The
+    /// collision probability for two sets with true Jaccard `s` is `1-(1-s^r)^b`, so `b`
+    /// and `r` trade recall of near-duplicates against candidate-set (and thus ranking)
+    /// size; falls back to a full scan when a query produces zero candidates.
+    #[cfg(feature = "mappy-integration")]
+    pub async fn benchmark_similarity_search_lsh(
+        tag_sets: &[Vec<String>],
+        query_tags: &[String],
+        top_k: usize,
+        bands: usize,
+        rows_per_band: usize,
+    ) -> Result<MLTaskResults> {
+        let signature_size = bands * rows_per_band;
+
+        // Exact: Direct tag set comparison
+        let start = Instant::now();
+        let exact_results = TagSimilarity::find_most_similar(query_tags, tag_sets, top_k);
+        let exact_time = start.elapsed().as_secs_f64() * 1000.0;
+
+        let posting_lists = Maplet::<String, Vec<u8>, PostingListOperator>::new(
+            tag_sets.len() * bands * 3,
+            0.01,
+        )?;
+
+        let mut cached_signatures: Vec<Vec<u64>> = Vec::with_capacity(tag_sets.len());
+        for (idx, tags) in tag_sets.iter().enumerate() {
+            let signature = MappyTagStorage::minhash_signature_with_size(tags, signature_size);
+            for (band_idx, band_rows) in signature.chunks(rows_per_band).enumerate() {
+                let key = Self::lsh_band_key(band_idx, band_rows);
+                posting_lists
+                    .insert(key, (idx as u32).to_le_bytes().to_vec())
+                    .await?;
+            }
+            cached_signatures.push(signature);
+        }
+
+        let query_signature =
+            MappyTagStorage::minhash_signature_with_size(query_tags, signature_size);
+
+        let start = Instant::now();
+        let mut candidates: HashSet<usize> = HashSet::new();
+        for (band_idx, band_rows) in query_signature.chunks(rows_per_band).enumerate() {
+            let key = Self::lsh_band_key(band_idx, band_rows);
+            if let Some(bytes) = posting_lists.query(&key).await {
+                for chunk in bytes.chunks_exact(4) {
+                    let idx = u32::from_le_bytes(chunk.try_into().unwrap()) as usize;
+                    candidates.insert(idx);
+                }
+            }
+        }
+
+        // Fall back to a full scan when no band collided with anything, rather than
+        // silently returning an empty result set.
+        if candidates.is_empty() {
+            candidates.extend(0..tag_sets.len());
+        }
+        let candidate_set_ratio = candidates.len() as f64 / tag_sets.len().max(1) as f64;
+
+        let mut approximate_results: Vec<(usize, f64)> = candidates
+            .into_iter()
+            .map(|idx| {
+                (
+                    idx,
+                    mappy_integration::approx_jaccard(&query_signature, &cached_signatures[idx]),
+                )
+            })
+            .collect();
+        approximate_results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+        approximate_results.truncate(top_k);
+        let approximate_time = start.elapsed().as_secs_f64() * 1000.0;
+
+        let exact_set: HashSet<usize> = exact_results.iter().map(|(idx, _)| *idx).collect();
+        let approximate_set: HashSet<usize> =
+            approximate_results.iter().map(|(idx, _)| *idx).collect();
+        let intersection = exact_set.intersection(&approximate_set).count();
+        let accuracy = intersection as f64 / top_k as f64;
+
+        Ok(MLTaskResults::new(
+            "similarity_search_lsh".to_string(),
+            accuracy,
+            accuracy,
+            exact_time,
+            approximate_time,
+        )
+        .with_candidate_set_ratio(candidate_set_ratio))
+    }
+
+    /// Benchmark a hybrid retrieval mode that fuses `TagSimilarity` (Jaccard) and
+    /// `TagEmbedding` (cosine) rankings via reciprocal rank fusion, rather than running
+    /// them as two separate benchmarks. Each ranked list is first filtered by its own
+    /// minimum-score cutoff, then every surviving item's fused score is the sum, across
+    /// whichever lists it appears in, of `1/(k + rank)` (rank is 1-indexed; `k` ~ 60
+    /// dampens the influence of a single list's top hit). Comparing the fused top-k
+    /// against the exact Jaccard baseline shows whether combining signals recovers items
+    /// that either signal alone misses.
+    #[cfg(feature = "mappy-integration")]
+    pub async fn benchmark_hybrid_search(
+        tag_sets: &[Vec<String>],
+        query_tags: &[String],
+        top_k: usize,
+        min_score_tag: f64,
+        min_score_vector: f64,
+    ) -> Result<MLTaskResults> {
+        const RRF_K: f64 = 60.0;
+
+        let start = Instant::now();
+        let exact_results = TagSimilarity::find_most_similar(query_tags, tag_sets, top_k);
+        let exact_time = start.elapsed().as_secs_f64() * 1000.0;
+
+        let vocabulary = TagEmbedding::build_vocabulary(tag_sets);
+        let query_embedding = TagEmbedding::embed(query_tags, &vocabulary);
+
+        let start = Instant::now();
+
+        let mut tag_ranked: Vec<(usize, f64)> = tag_sets
+            .iter()
+            .enumerate()
+            .map(|(idx, tags)| (idx, TagSimilarity::jaccard_similarity(query_tags, tags)))
+            .filter(|(_, score)| *score >= min_score_tag)
+            .collect();
+        tag_ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+
+        let mut vector_ranked: Vec<(usize, f64)> = tag_sets
+            .iter()
+            .enumerate()
+            .map(|(idx, tags)| {
+                let embedding = TagEmbedding::embed(tags, &vocabulary);
+                (idx, TagEmbedding::embedding_similarity(&query_embedding, &embedding))
+            })
+            .filter(|(_, score)| *score >= min_score_vector)
+            .collect();
+        vector_ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+
+        let mut fused_scores: HashMap<usize, f64> = HashMap::new();
+        for (rank, (idx, _)) in tag_ranked.iter().enumerate() {
+            *fused_scores.entry(*idx).or_insert(0.0) += 1.0 / (RRF_K + (rank + 1) as f64);
+        }
+        for (rank, (idx, _)) in vector_ranked.iter().enumerate() {
+            *fused_scores.entry(*idx).or_insert(0.0) += 1.0 / (RRF_K + (rank + 1) as f64);
+        }
+
+        let mut hybrid_results: Vec<(usize, f64)> = fused_scores.into_iter().collect();
+        hybrid_results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+        hybrid_results.truncate(top_k);
+        let approximate_time = start.elapsed().as_secs_f64() * 1000.0;
+
+        let exact_set: HashSet<usize> = exact_results.iter().map(|(idx, _)| *idx).collect();
+        let hybrid_set: HashSet<usize> = hybrid_results.iter().map(|(idx, _)| *idx).collect();
+        let intersection = exact_set.intersection(&hybrid_set).count();
+        let recovered_ratio = intersection as f64 / top_k as f64;
+
+        Ok(MLTaskResults::new(
+            "hybrid_search".to_string(),
+            1.0, // the exact Jaccard baseline trivially matches itself
+            recovered_ratio,
+            exact_time,
+            approximate_time,
         ))
     }
-    
+
     /// Benchmark classification: exact vs approximate
     #[cfg(feature = "mappy-integration")]
     pub async fn benchmark_classification(
@@ -174,25 +559,25 @@ impl MLBenchmarkRunner {
             training_data.len() * 3, // Extra capacity to avoid overflow
             0.01,
         )?;
-        
-        // Store training data with proper indexed keys
-        for (idx, (_class, tags)) in training_data.iter().enumerate() {
-            let compressed = storage.compress_tags(tags)?;
-            let key = format!("train_{}", idx);
-            mappy_maplet.insert(key, compressed).await?;
-        }
-        
-        // Approximate classification - optimized: cache decompressed training data
-        // Pre-decompress all training examples to avoid redundant mappy queries
+
+        // Content-addressed store: duplicate training examples collapse to one entry.
+        let training_tags: Vec<Vec<String>> =
+            training_data.iter().map(|(_, tags)| tags.clone()).collect();
+        let (digests, dedup_ratio) =
+            Self::store_content_addressed(&mut storage, &training_tags, &mappy_maplet).await?;
+
+        // Approximate classification - optimized: cache decompressed training data via a
+        // single batch call, avoiding redundant mappy queries and redundant decompression
+        // of duplicate training examples.
+        let decompressed_by_digest =
+            Self::decompress_batch_from_maplet(&storage, &mappy_maplet, &digests).await?;
         let mut cached_training: Vec<(String, Vec<String>)> = Vec::new();
         for (idx, (class, _)) in training_data.iter().enumerate() {
-            let key = format!("train_{}", idx);
-            if let Some(compressed) = mappy_maplet.query(&key).await {
-                let decompressed = storage.decompress_tags(&compressed)?;
-                cached_training.push((class.clone(), decompressed));
+            if let Some(decompressed) = decompressed_by_digest.get(&digests[idx]) {
+                cached_training.push((class.clone(), decompressed.clone()));
             }
         }
-        
+
         let start = Instant::now();
         let mut approximate_correct = 0;
         
@@ -238,9 +623,10 @@ impl MLBenchmarkRunner {
             approximate_accuracy,
             exact_time,
             approximate_time,
-        ))
+        )
+        .with_dedup_ratio(dedup_ratio))
     }
-    
+
     /// Benchmark clustering: exact vs approximate
     #[cfg(feature = "mappy-integration")]
     pub async fn benchmark_clustering(
@@ -262,22 +648,18 @@ impl MLBenchmarkRunner {
             tag_sets.len() * 3, // Extra capacity to avoid overflow
             0.01,
         )?;
-        
-        for (idx, tags) in tag_sets.iter().enumerate() {
-            let compressed = storage.compress_tags(tags)?;
-            mappy_maplet.insert(format!("item_{}", idx), compressed).await?;
-        }
-        
+
+        let (digests, dedup_ratio) =
+            Self::store_content_addressed(&mut storage, tag_sets, &mappy_maplet).await?;
+
         let start = Instant::now();
-        let mut retrieved_sets = Vec::new();
-        
-        for idx in 0..tag_sets.len() {
-            if let Some(compressed) = mappy_maplet.query(&format!("item_{}", idx)).await {
-                let decompressed = storage.decompress_tags(&compressed)?;
-                retrieved_sets.push(decompressed);
-            }
-        }
-        
+        let decompressed_by_digest =
+            Self::decompress_batch_from_maplet(&storage, &mappy_maplet, &digests).await?;
+        let retrieved_sets: Vec<Vec<String>> = digests
+            .iter()
+            .filter_map(|digest| decompressed_by_digest.get(digest).cloned())
+            .collect();
+
         let approximate_clusters = TagClustering::cluster(&retrieved_sets, k, 10);
         let approximate_time = start.elapsed().as_secs_f64() * 1000.0;
         
@@ -297,9 +679,10 @@ impl MLBenchmarkRunner {
             accuracy,
             exact_time,
             approximate_time,
-        ))
+        )
+        .with_dedup_ratio(dedup_ratio))
     }
-    
+
     /// Benchmark embedding generation: exact vs approximate
     /// 
     /// OPTIMIZED: Caches vocabulary building and uses efficient batch operations
@@ -327,25 +710,23 @@ impl MLBenchmarkRunner {
             tag_sets.len() * 3, // Extra capacity to avoid overflow
             0.01,
         )?;
-        
-        // Batch insert all tag sets
-        for (idx, tags) in tag_sets.iter().enumerate() {
-            let compressed = storage.compress_tags(tags)?;
-            mappy_maplet.insert(format!("item_{}", idx), compressed).await?;
-        }
-        
-        // OPTIMIZATION: Pre-decompress and cache all tag sets (like similarity search)
-        // This moves mappy queries out of the hot path
+
+        // Content-addressed store: duplicate tag sets collapse to one entry.
+        let (digests, dedup_ratio) =
+            Self::store_content_addressed(&mut storage, tag_sets, &mappy_maplet).await?;
+
+        // OPTIMIZATION: Pre-decompress and cache all tag sets (like similarity search), in
+        // one batch call. This moves mappy queries out of the hot path.
+        let decompressed_by_digest =
+            Self::decompress_batch_from_maplet(&storage, &mappy_maplet, &digests).await?;
         let mut cached_tag_sets: Vec<Vec<String>> = Vec::with_capacity(tag_sets.len());
         for (idx, original_tags) in tag_sets.iter().enumerate() {
-            if let Some(compressed) = mappy_maplet.query(&format!("item_{}", idx)).await {
-                let decompressed = storage.decompress_tags(&compressed)?;
-                cached_tag_sets.push(decompressed);
-            } else {
-                cached_tag_sets.push(original_tags.clone());
+            match decompressed_by_digest.get(&digests[idx]) {
+                Some(decompressed) => cached_tag_sets.push(decompressed.clone()),
+                None => cached_tag_sets.push(original_tags.clone()),
             }
         }
-        
+
         // Build vocabulary from cached tag sets (cached approach)
         let approximate_vocab = TagEmbedding::build_vocabulary(&cached_tag_sets);
         
@@ -363,9 +744,10 @@ impl MLBenchmarkRunner {
             similarity,
             exact_time,
             approximate_time,
-        ))
+        )
+        .with_dedup_ratio(dedup_ratio))
     }
-    
+
     /// Run comprehensive ML benchmarks
     #[cfg(feature = "mappy-integration")]
     pub async fn run_comprehensive_benchmarks() -> Result<Vec<MLTaskResults>> {
@@ -378,7 +760,22 @@ impl MLBenchmarkRunner {
         println!("Running similarity search benchmark...");
         let similarity_result = Self::benchmark_similarity_search(&tag_sets, &query_tags, 10).await?;
         results.push(similarity_result);
-        
+
+        println!("Running MinHash similarity search benchmark...");
+        let minhash_result =
+            Self::benchmark_similarity_search_minhash(&tag_sets, &query_tags, 10).await?;
+        results.push(minhash_result);
+
+        println!("Running LSH similarity search benchmark...");
+        let lsh_result =
+            Self::benchmark_similarity_search_lsh(&tag_sets, &query_tags, 10, 16, 8).await?;
+        results.push(lsh_result);
+
+        println!("Running hybrid tag+embedding search benchmark...");
+        let hybrid_result =
+            Self::benchmark_hybrid_search(&tag_sets, &query_tags, 10, 0.0, 0.0).await?;
+        results.push(hybrid_result);
+
         println!("Running clustering benchmark...");
         let clustering_result = Self::benchmark_clustering(&tag_sets, 5).await?;
         results.push(clustering_result);
@@ -436,7 +833,15 @@ impl MLBenchmarkRunner {
             5
         ).await?;
         results.push(classification_result);
-        
+
+        println!("Running block-batched decode benchmark...");
+        // Clustered indices (repeated, ascending within a small window) so several
+        // requests land in the same block.
+        let query_indices: Vec<usize> = (0..10).flat_map(|base| [base, base, base + 1]).collect();
+        let block_batched_result =
+            Self::benchmark_block_batched_decode(&tag_sets, &query_indices, 10).await?;
+        results.push(block_batched_result);
+
         Ok(results)
     }
 }
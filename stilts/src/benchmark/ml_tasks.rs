@@ -4,12 +4,146 @@
 //! stored in mappy, demonstrating that the approximate nature of mappy doesn't
 //! hurt ML performance.
 
+use crate::compression::HyperLogLog;
+use crate::formats::AliasTable;
 use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+/// A MinHash signature for a tag set: `k` independent `(a*h+b) mod p` hash functions, each
+/// keeping the minimum value seen across the set's tags. `estimate_jaccard` between two
+/// signatures (the fraction of positions where they agree) is an unbiased estimator of
+/// exact Jaccard similarity with error ~1/sqrt(k), letting `TagSimilarity` rank thousands of
+/// candidates without recomputing full set intersections. This is a self-contained sketch
+/// for in-memory ranking; see `mappy_integration::MappyTagStorage::minhash_signature` for
+/// the equivalent used when signatures need to be stored in a `Maplet`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinHashSketch {
+    signature: Vec<u64>,
+}
+
+impl MinHashSketch {
+    /// Default signature length; 128 keeps the estimator error small while staying cheap.
+    pub const DEFAULT_SIZE: usize = 128;
+
+    /// Build a `k`-minimum-values signature for `tags`. An empty tag set gets an
+    /// all-`u64::MAX` signature, so `estimate_jaccard` against it is always 0.0.
+    pub fn new(tags: &[String], k: usize) -> Self {
+        if tags.is_empty() {
+            return Self { signature: vec![u64::MAX; k] };
+        }
+
+        let tag_hashes: Vec<u64> = tags.iter().map(|tag| Self::hash_tag(tag)).collect();
+        let signature = Self::coefficients(k)
+            .iter()
+            .map(|&(a, b)| {
+                tag_hashes
+                    .iter()
+                    .map(|&h| Self::hash_function(a, b, h))
+                    .min()
+                    .unwrap_or(u64::MAX)
+            })
+            .collect();
+        Self { signature }
+    }
+
+    /// Number of hash functions in this signature.
+    pub fn len(&self) -> usize {
+        self.signature.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.signature.is_empty()
+    }
+
+    /// Estimate Jaccard similarity as the fraction of signature positions that agree.
+    /// Mismatched signature sizes (sketches must be built with the same `k` to compare) and
+    /// an empty signature both return 0.0 rather than panicking.
+    pub fn estimate_jaccard(&self, other: &Self) -> f64 {
+        if self.signature.is_empty() || self.signature.len() != other.signature.len() {
+            return 0.0;
+        }
+        if self.signature.iter().all(|&v| v == u64::MAX) || other.signature.iter().all(|&v| v == u64::MAX) {
+            return 0.0;
+        }
+        let agreeing = self
+            .signature
+            .iter()
+            .zip(other.signature.iter())
+            .filter(|(a, b)| a == b)
+            .count();
+        agreeing as f64 / self.signature.len() as f64
+    }
+
+    /// Split the signature into `bands` equal-length bands and hash each one down to a
+    /// single bucket id, for LSH candidate generation: two sketches sharing a bucket in any
+    /// band are collision candidates worth ranking exactly. Returns an empty `Vec` if
+    /// `bands` doesn't evenly divide the signature length or the signature is empty.
+    pub fn lsh_buckets(&self, bands: usize) -> Vec<u64> {
+        if bands == 0 || self.signature.is_empty() || self.signature.len() % bands != 0 {
+            return Vec::new();
+        }
+        let rows_per_band = self.signature.len() / bands;
+        self.signature
+            .chunks(rows_per_band)
+            .map(|rows| {
+                let mut hasher = std::collections::hash_map::DefaultHasher::new();
+                rows.hash(&mut hasher);
+                hasher.finish()
+            })
+            .collect()
+    }
+
+    fn hash_tag(tag: &str) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        tag.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// A large Mersenne prime (2^61 - 1), standard for MinHash's `(a*h+b) mod p` family.
+    const PRIME: u64 = (1u64 << 61) - 1;
+
+    fn hash_function(a: u64, b: u64, x: u64) -> u64 {
+        ((a as u128 * x as u128 + b as u128) % Self::PRIME as u128) as u64
+    }
+
+    /// Deterministic (not OS-random) `(a, b)` coefficients for `n` independent hash
+    /// functions, seeded with a fixed constant via `SplitMix64` so two calls with the same
+    /// `n` always produce comparable signatures.
+    fn coefficients(n: usize) -> Vec<(u64, u64)> {
+        fn splitmix64(state: &mut u64) -> u64 {
+            *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = *state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        }
+
+        let mut state = 0x5EED_1357_F00D_CAFEu64;
+        (0..n)
+            .map(|_| (splitmix64(&mut state), splitmix64(&mut state)))
+            .collect()
+    }
+}
 
 /// Tag similarity metrics
 pub struct TagSimilarity;
 
 impl TagSimilarity {
+    /// Like `jaccard_similarity`, but first canonicalizes every tag through `alias_table` so
+    /// synonyms and split/concat variants of the same compound word (`canine`/`canid`,
+    /// `sharpteeth`/`sharp_teeth`) collapse to one representative before the set comparison,
+    /// letting approximate retrieval match semantically-equivalent tag sets that don't share
+    /// exact strings.
+    pub fn jaccard_similarity_expanded(
+        tags1: &[String],
+        tags2: &[String],
+        alias_table: &AliasTable,
+    ) -> f64 {
+        let canonical1: Vec<String> = tags1.iter().map(|tag| alias_table.canonicalize(tag)).collect();
+        let canonical2: Vec<String> = tags2.iter().map(|tag| alias_table.canonicalize(tag)).collect();
+        Self::jaccard_similarity(&canonical1, &canonical2)
+    }
+
     /// Calculate Jaccard similarity between two tag sets
     pub fn jaccard_similarity(tags1: &[String], tags2: &[String]) -> f64 {
         let set1: HashSet<&str> = tags1.iter().map(|s| s.as_str()).collect();
@@ -63,22 +197,94 @@ impl TagSimilarity {
         freq
     }
     
-    /// Find most similar tag sets using Jaccard similarity
+    /// Find most similar tag sets using Jaccard similarity. With the `rayon-parallel`
+    /// feature, the per-candidate similarity map runs across a Rayon thread pool; results
+    /// are unaffected since `par_iter().enumerate()` preserves candidate order and the
+    /// final sort is stable.
     pub fn find_most_similar(
         query_tags: &[String],
         candidate_sets: &[Vec<String>],
         top_k: usize,
     ) -> Vec<(usize, f64)> {
+        #[cfg(feature = "rayon-parallel")]
+        let mut similarities: Vec<(usize, f64)> = {
+            use rayon::prelude::*;
+            candidate_sets
+                .par_iter()
+                .enumerate()
+                .map(|(idx, tags)| (idx, Self::jaccard_similarity(query_tags, tags)))
+                .collect()
+        };
+        #[cfg(not(feature = "rayon-parallel"))]
         let mut similarities: Vec<(usize, f64)> = candidate_sets
             .iter()
             .enumerate()
             .map(|(idx, tags)| (idx, Self::jaccard_similarity(query_tags, tags)))
             .collect();
-        
+
         similarities.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
         similarities.truncate(top_k);
         similarities
     }
+
+    /// Default LSH band count for `find_most_similar_approx`; paired with
+    /// `MinHashSketch::DEFAULT_SIZE` (128) this gives 8 rows per band.
+    pub const DEFAULT_LSH_BANDS: usize = 16;
+
+    /// Build one `MinHashSketch` per tag set, so a corpus only needs to be sketched once
+    /// before running many `find_most_similar_approx` queries against it.
+    pub fn build_sketches(tag_sets: &[Vec<String>], k: usize) -> Vec<MinHashSketch> {
+        tag_sets.iter().map(|tags| MinHashSketch::new(tags, k)).collect()
+    }
+
+    /// Like `find_most_similar`, but over `MinHashSketch`es instead of raw tag sets: uses
+    /// `DEFAULT_LSH_BANDS` to prune candidates before ranking. See
+    /// `find_most_similar_approx_banded` to control the band count directly.
+    pub fn find_most_similar_approx(
+        query: &MinHashSketch,
+        candidate_sketches: &[MinHashSketch],
+        top_k: usize,
+    ) -> Vec<(usize, f64)> {
+        Self::find_most_similar_approx_banded(query, candidate_sketches, top_k, Self::DEFAULT_LSH_BANDS)
+    }
+
+    /// Find the `top_k` candidates most similar to `query` by estimated Jaccard, first
+    /// narrowing to candidates that share at least one LSH bucket with `query` (see
+    /// `MinHashSketch::lsh_buckets`) so large corpora skip ranking everything. Falls back to
+    /// ranking every candidate if no bucket collides, rather than returning nothing.
+    pub fn find_most_similar_approx_banded(
+        query: &MinHashSketch,
+        candidate_sketches: &[MinHashSketch],
+        top_k: usize,
+        bands: usize,
+    ) -> Vec<(usize, f64)> {
+        let query_buckets = query.lsh_buckets(bands);
+
+        let mut candidate_indices: Vec<usize> = candidate_sketches
+            .iter()
+            .enumerate()
+            .filter(|(_, sketch)| {
+                sketch
+                    .lsh_buckets(bands)
+                    .iter()
+                    .zip(query_buckets.iter())
+                    .any(|(a, b)| a == b)
+            })
+            .map(|(idx, _)| idx)
+            .collect();
+
+        if candidate_indices.is_empty() {
+            candidate_indices = (0..candidate_sketches.len()).collect();
+        }
+
+        let mut results: Vec<(usize, f64)> = candidate_indices
+            .into_iter()
+            .map(|idx| (idx, query.estimate_jaccard(&candidate_sketches[idx])))
+            .collect();
+        results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+        results.truncate(top_k);
+        results
+    }
 }
 
 /// Tag-based classification
@@ -99,17 +305,30 @@ impl TagClassifier {
         Self { class_tags }
     }
     
-    /// Classify tags using k-nearest neighbors
+    /// Classify tags using k-nearest neighbors. With the `rayon-parallel` feature, the
+    /// per-example similarity computation runs across a Rayon thread pool; the flattened
+    /// `(class, tags)` list is built the same way either way, so results are unaffected.
     pub fn classify_knn(&self, query_tags: &[String], k: usize) -> Vec<(String, f64)> {
-        let mut all_similarities: Vec<(String, f64)> = Vec::new();
-        
-        for (class, tag_sets) in &self.class_tags {
-            for tags in tag_sets {
-                let similarity = TagSimilarity::jaccard_similarity(query_tags, tags);
-                all_similarities.push((class.clone(), similarity));
-            }
-        }
-        
+        let flattened: Vec<(&String, &Vec<String>)> = self
+            .class_tags
+            .iter()
+            .flat_map(|(class, tag_sets)| tag_sets.iter().map(move |tags| (class, tags)))
+            .collect();
+
+        #[cfg(feature = "rayon-parallel")]
+        let mut all_similarities: Vec<(String, f64)> = {
+            use rayon::prelude::*;
+            flattened
+                .par_iter()
+                .map(|(class, tags)| ((*class).clone(), TagSimilarity::jaccard_similarity(query_tags, tags)))
+                .collect()
+        };
+        #[cfg(not(feature = "rayon-parallel"))]
+        let mut all_similarities: Vec<(String, f64)> = flattened
+            .iter()
+            .map(|(class, tags)| ((*class).clone(), TagSimilarity::jaccard_similarity(query_tags, tags)))
+            .collect();
+
         // Sort by similarity and get top k
         all_similarities.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
         
@@ -131,11 +350,159 @@ impl TagClassifier {
     }
 }
 
+/// Hyperparameters for `TagLinearClassifier::train`.
+#[derive(Debug, Clone, Copy)]
+pub struct LinearClassifierConfig {
+    pub learning_rate: f64,
+    pub l2: f64,
+    pub epochs: usize,
+    /// Examples per gradient step; training data is batched in its given order (not
+    /// shuffled), so a training run is fully reproducible from the same input.
+    pub batch_size: usize,
+    /// Weight features by `TagEmbedding::fit`'s IDF instead of plain 0/1 presence.
+    pub use_idf: bool,
+}
+
+impl Default for LinearClassifierConfig {
+    fn default() -> Self {
+        Self {
+            learning_rate: 0.1,
+            l2: 0.001,
+            epochs: 50,
+            batch_size: 32,
+            use_idf: true,
+        }
+    }
+}
+
+/// One-vs-rest logistic regression over a tag vocabulary: an `O(|vocabulary| * |classes|)`
+/// alternative to `TagClassifier::classify_knn`'s full training-set scan per query. After
+/// training, only one weight vector per class is kept (the last entry is that class's
+/// bias), so the model stays tiny enough to store compressed in mappy.
+pub struct TagLinearClassifier {
+    vocabulary: Vec<String>,
+    idf_model: Option<TfidfModel>,
+    /// One `vocabulary.len() + 1`-length weight vector (trailing entry is the bias) per
+    /// class.
+    weights: HashMap<String, Vec<f64>>,
+}
+
+impl TagLinearClassifier {
+    /// Fit one-vs-rest logistic regression weights by mini-batch gradient descent on the
+    /// logistic loss.
+    pub fn train(training_data: &[(String, Vec<String>)], config: &LinearClassifierConfig) -> Self {
+        let tag_sets: Vec<Vec<String>> = training_data.iter().map(|(_, tags)| tags.clone()).collect();
+        let idf_model = if config.use_idf {
+            Some(TagEmbedding::fit(&tag_sets))
+        } else {
+            None
+        };
+        let vocabulary = idf_model
+            .as_ref()
+            .map(|model| model.vocabulary().to_vec())
+            .unwrap_or_else(|| TagEmbedding::build_vocabulary(&tag_sets));
+
+        let features: Vec<Vec<f64>> = tag_sets
+            .iter()
+            .map(|tags| Self::featurize(tags, &vocabulary, idf_model.as_ref()))
+            .collect();
+
+        let mut classes: Vec<String> = Vec::new();
+        let mut seen_classes: HashSet<&str> = HashSet::new();
+        for (class, _) in training_data {
+            if seen_classes.insert(class.as_str()) {
+                classes.push(class.clone());
+            }
+        }
+
+        let mut weights: HashMap<String, Vec<f64>> = HashMap::new();
+        for class in &classes {
+            let labels: Vec<f64> = training_data
+                .iter()
+                .map(|(c, _)| if c == class { 1.0 } else { 0.0 })
+                .collect();
+            weights.insert(class.clone(), Self::fit_one_vs_rest(&features, &labels, vocabulary.len(), config));
+        }
+
+        Self {
+            vocabulary,
+            idf_model,
+            weights,
+        }
+    }
+
+    /// Classes ranked by calibrated (sigmoid) score, highest first.
+    pub fn predict(&self, query_tags: &[String]) -> Vec<(String, f64)> {
+        let x = Self::featurize(query_tags, &self.vocabulary, self.idf_model.as_ref());
+        let mut results: Vec<(String, f64)> = self
+            .weights
+            .iter()
+            .map(|(class, w)| (class.clone(), Self::sigmoid(Self::dot_with_bias(w, &x))))
+            .collect();
+        results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+        results
+    }
+
+    fn featurize(tags: &[String], vocabulary: &[String], idf_model: Option<&TfidfModel>) -> Vec<f64> {
+        match idf_model {
+            Some(model) => model.embed_tfidf(tags),
+            None => TagEmbedding::embed(tags, vocabulary),
+        }
+    }
+
+    fn fit_one_vs_rest(
+        features: &[Vec<f64>],
+        labels: &[f64],
+        dim: usize,
+        config: &LinearClassifierConfig,
+    ) -> Vec<f64> {
+        let mut w = vec![0.0; dim + 1];
+        if features.is_empty() {
+            return w;
+        }
+        let batch_size = config.batch_size.max(1);
+
+        for _epoch in 0..config.epochs {
+            for (batch_features, batch_labels) in features.chunks(batch_size).zip(labels.chunks(batch_size)) {
+                let mut gradient = vec![0.0; dim + 1];
+                for (x, &y) in batch_features.iter().zip(batch_labels.iter()) {
+                    let error = Self::sigmoid(Self::dot_with_bias(&w, x)) - y;
+                    for (g, &xi) in gradient.iter_mut().zip(x.iter()) {
+                        *g += error * xi;
+                    }
+                    *gradient.last_mut().unwrap() += error;
+                }
+
+                let batch_len = batch_features.len() as f64;
+                for (wi, gi) in w.iter_mut().zip(gradient.iter()) {
+                    *wi -= config.learning_rate * (gi / batch_len + config.l2 * *wi);
+                }
+            }
+        }
+
+        w
+    }
+
+    /// Dot product of `x` against `w`'s feature weights plus `w`'s trailing bias term.
+    fn dot_with_bias(w: &[f64], x: &[f64]) -> f64 {
+        let bias = *w.last().unwrap_or(&0.0);
+        w[..w.len() - 1].iter().zip(x.iter()).map(|(a, b)| a * b).sum::<f64>() + bias
+    }
+
+    fn sigmoid(z: f64) -> f64 {
+        1.0 / (1.0 + (-z).exp())
+    }
+}
+
 /// Tag clustering using simple k-means-like approach
 pub struct TagClustering;
 
 impl TagClustering {
-    /// Cluster tag sets into k clusters
+    /// Cluster tag sets into k clusters. With the `rayon-parallel` feature, the
+    /// per-point nearest-centroid assignment and the centroid-update tag-count reduction
+    /// both run across a Rayon thread pool; since assignment is a pure per-point function
+    /// of the current centroids (not accumulated in iteration order) and the tag-count
+    /// reduction is an associative merge, results are identical to the serial path.
     pub fn cluster(
         tag_sets: &[Vec<String>],
         k: usize,
@@ -144,10 +511,10 @@ impl TagClustering {
         if tag_sets.is_empty() || k == 0 {
             return vec![];
         }
-        
+
         let k = k.min(tag_sets.len());
         let mut assignments = vec![0; tag_sets.len()];
-        
+
         // Initialize centroids randomly
         let mut centroids: Vec<Vec<String>> = Vec::new();
         for i in 0..k {
@@ -155,33 +522,30 @@ impl TagClustering {
                 centroids.push(tag_sets[i].clone());
             }
         }
-        
+
         for _iteration in 0..max_iterations {
-            let mut changed = false;
-            
-            // Assign each tag set to nearest centroid
-            for (idx, tags) in tag_sets.iter().enumerate() {
-                let mut best_cluster = 0;
-                let mut best_similarity = -1.0;
-                
-                for (cluster_idx, centroid) in centroids.iter().enumerate() {
-                    let similarity = TagSimilarity::jaccard_similarity(tags, centroid);
-                    if similarity > best_similarity {
-                        best_similarity = similarity;
-                        best_cluster = cluster_idx;
-                    }
-                }
-                
-                if assignments[idx] != best_cluster {
-                    changed = true;
-                    assignments[idx] = best_cluster;
-                }
-            }
-            
+            // Assign each tag set to its nearest centroid.
+            #[cfg(feature = "rayon-parallel")]
+            let new_assignments: Vec<usize> = {
+                use rayon::prelude::*;
+                tag_sets
+                    .par_iter()
+                    .map(|tags| Self::nearest_centroid(tags, &centroids))
+                    .collect()
+            };
+            #[cfg(not(feature = "rayon-parallel"))]
+            let new_assignments: Vec<usize> = tag_sets
+                .iter()
+                .map(|tags| Self::nearest_centroid(tags, &centroids))
+                .collect();
+
+            let changed = new_assignments != assignments;
+            assignments = new_assignments;
+
             if !changed {
                 break;
             }
-            
+
             // Update centroids (average tags in each cluster)
             for cluster_idx in 0..k {
                 let cluster_tags: Vec<&Vec<String>> = tag_sets
@@ -190,26 +554,63 @@ impl TagClustering {
                     .filter(|(idx, _)| assignments[*idx] == cluster_idx)
                     .map(|(_, tags)| tags)
                     .collect();
-                
+
                 if !cluster_tags.is_empty() {
                     centroids[cluster_idx] = Self::compute_centroid(cluster_tags);
                 }
             }
         }
-        
+
         assignments
     }
-    
+
+    /// The index of `tags`' nearest centroid by Jaccard similarity.
+    fn nearest_centroid(tags: &[String], centroids: &[Vec<String>]) -> usize {
+        let mut best_cluster = 0;
+        let mut best_similarity = -1.0;
+
+        for (cluster_idx, centroid) in centroids.iter().enumerate() {
+            let similarity = TagSimilarity::jaccard_similarity(tags, centroid);
+            if similarity > best_similarity {
+                best_similarity = similarity;
+                best_cluster = cluster_idx;
+            }
+        }
+
+        best_cluster
+    }
+
     /// Compute centroid as most common tags in cluster
     fn compute_centroid(cluster_tags: Vec<&Vec<String>>) -> Vec<String> {
-        let mut tag_counts: HashMap<&str, usize> = HashMap::new();
-        
-        for tags in &cluster_tags {
-            for tag in *tags {
-                *tag_counts.entry(tag.as_str()).or_insert(0) += 1;
+        #[cfg(feature = "rayon-parallel")]
+        let tag_counts: HashMap<&str, usize> = {
+            use rayon::prelude::*;
+            cluster_tags
+                .par_iter()
+                .fold(HashMap::new, |mut counts: HashMap<&str, usize>, tags| {
+                    for tag in tags.iter() {
+                        *counts.entry(tag.as_str()).or_insert(0) += 1;
+                    }
+                    counts
+                })
+                .reduce(HashMap::new, |mut a, b| {
+                    for (tag, count) in b {
+                        *a.entry(tag).or_insert(0) += count;
+                    }
+                    a
+                })
+        };
+        #[cfg(not(feature = "rayon-parallel"))]
+        let tag_counts: HashMap<&str, usize> = {
+            let mut counts = HashMap::new();
+            for tags in &cluster_tags {
+                for tag in *tags {
+                    *counts.entry(tag.as_str()).or_insert(0) += 1;
+                }
             }
-        }
-        
+            counts
+        };
+
         // Take top tags that appear in at least 50% of tag sets
         let threshold = cluster_tags.len() / 2;
         let mut centroid: Vec<String> = tag_counts
@@ -217,7 +618,7 @@ impl TagClustering {
             .filter(|(_, count)| *count >= threshold)
             .map(|(tag, _)| tag.to_string())
             .collect();
-        
+
         centroid.sort();
         centroid
     }
@@ -274,6 +675,251 @@ impl TagEmbedding {
             dot_product / (norm1 * norm2)
         }
     }
+
+    /// Fit a `TfidfModel` over `tag_sets`: document frequency per vocabulary term gives
+    /// `idf = ln((N+1)/(df+1)) + 1`, the "smooth" IDF also used by scikit-learn-style
+    /// vectorizers (always positive, and still defined when a term appears in every set).
+    pub fn fit(tag_sets: &[Vec<String>]) -> TfidfModel {
+        let vocabulary = Self::build_vocabulary(tag_sets);
+        let n = tag_sets.len() as f64;
+
+        let mut doc_freq: HashMap<&str, usize> = HashMap::new();
+        for tags in tag_sets {
+            let unique: HashSet<&str> = tags.iter().map(|s| s.as_str()).collect();
+            for term in unique {
+                *doc_freq.entry(term).or_insert(0) += 1;
+            }
+        }
+
+        let idf: Vec<f64> = vocabulary
+            .iter()
+            .map(|term| {
+                let df = doc_freq.get(term.as_str()).copied().unwrap_or(0) as f64;
+                ((n + 1.0) / (df + 1.0)).ln() + 1.0
+            })
+            .collect();
+
+        let mut max_weight = vec![0.0; vocabulary.len()];
+        for tags in tag_sets {
+            let tf = Self::term_frequencies(tags);
+            for (dim, term) in vocabulary.iter().enumerate() {
+                let weight = tf.get(term.as_str()).copied().unwrap_or(0.0) * idf[dim];
+                if weight > max_weight[dim] {
+                    max_weight[dim] = weight;
+                }
+            }
+        }
+
+        TfidfModel {
+            vocabulary,
+            idf,
+            max_weight,
+        }
+    }
+
+    /// Per-tag occurrence count within a single tag set (the `tf` in `tf*idf`).
+    fn term_frequencies(tags: &[String]) -> HashMap<&str, f64> {
+        let mut freq: HashMap<&str, f64> = HashMap::new();
+        for tag in tags {
+            *freq.entry(tag.as_str()).or_insert(0.0) += 1.0;
+        }
+        freq
+    }
+}
+
+/// IDF weights (and quantization scale) fitted from a tag-set corpus by `TagEmbedding::fit`,
+/// so `embed_tfidf` can weight rare, discriminative tags more heavily than `embed`'s plain
+/// 0/1 presence vector, and compact vectors down to `u8` for storage without needing the
+/// original corpus again to dequantize them.
+pub struct TfidfModel {
+    vocabulary: Vec<String>,
+    idf: Vec<f64>,
+    /// Per-dimension max `tf*idf` weight seen while fitting; the quantization scale.
+    max_weight: Vec<f64>,
+}
+
+impl TfidfModel {
+    pub fn vocabulary(&self) -> &[String] {
+        &self.vocabulary
+    }
+
+    /// Emit the `tf*idf` embedding for `tags` over this model's vocabulary.
+    pub fn embed_tfidf(&self, tags: &[String]) -> Vec<f64> {
+        let tf = TagEmbedding::term_frequencies(tags);
+        self.vocabulary
+            .iter()
+            .zip(self.idf.iter())
+            .map(|(term, &idf)| tf.get(term.as_str()).copied().unwrap_or(0.0) * idf)
+            .collect()
+    }
+
+    /// Linearly map a `tf*idf` vector (from this model) into `u8`, scaling each dimension by
+    /// its fitted max weight so `[0, max_weight]` maps onto `[0, 255]`. A dimension whose max
+    /// weight is 0 (a vocabulary term absent from the fitted corpus) always quantizes to 0.
+    pub fn quantize(&self, vector: &[f64]) -> Vec<u8> {
+        vector
+            .iter()
+            .zip(self.max_weight.iter())
+            .map(|(&value, &max)| {
+                if max <= 0.0 {
+                    0
+                } else {
+                    ((value / max).clamp(0.0, 1.0) * 255.0).round() as u8
+                }
+            })
+            .collect()
+    }
+
+    /// Inverse of `quantize`, dequantizing back to `f64` weights for similarity scoring.
+    pub fn dequantize(&self, quantized: &[u8]) -> Vec<f64> {
+        quantized
+            .iter()
+            .zip(self.max_weight.iter())
+            .map(|(&q, &max)| (f64::from(q) / 255.0) * max)
+            .collect()
+    }
+
+    /// Cosine similarity between two quantized embeddings, dequantizing each before the dot
+    /// product so `TagEmbedding::embedding_similarity` doesn't need a `u8`-aware overload.
+    pub fn embedding_similarity_quantized(&self, emb1: &[u8], emb2: &[u8]) -> f64 {
+        TagEmbedding::embedding_similarity(&self.dequantize(emb1), &self.dequantize(emb2))
+    }
+}
+
+/// HyperLogLog-based cardinality estimator for tag vocabularies: an `O(2^precision)`-memory
+/// alternative to `TagEmbedding::build_vocabulary` when only the distinct-tag *count* is
+/// needed (not the tags themselves) over corpora too large to materialize a `HashSet` for.
+/// `merge` lets shards be estimated independently (e.g. in parallel) and then combined.
+pub struct TagCardinality {
+    hll: HyperLogLog,
+}
+
+impl TagCardinality {
+    /// A new estimator using `HyperLogLog`'s default precision (14, ~0.8% standard error).
+    pub fn new() -> Self {
+        Self { hll: HyperLogLog::new() }
+    }
+
+    /// A new estimator with `2^precision` registers.
+    pub fn with_precision(precision: u8) -> Self {
+        Self { hll: HyperLogLog::with_precision(precision) }
+    }
+
+    /// Add every tag in `tags` to the estimator.
+    pub fn add_tags(&mut self, tags: &[String]) {
+        for tag in tags {
+            self.hll.add(tag);
+        }
+    }
+
+    /// Merge `other`'s registers into this one (register-wise max) so cardinality can be
+    /// computed incrementally, or in parallel across shards and combined afterward. Both
+    /// estimators must share the same precision.
+    pub fn merge(&mut self, other: &Self) {
+        self.hll.merge(&other.hll);
+    }
+
+    /// Estimate the number of distinct tags added so far.
+    pub fn estimate(&self) -> f64 {
+        self.hll.estimate()
+    }
+}
+
+impl Default for TagCardinality {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Fuses a lexical ranking (`TagSimilarity::jaccard_similarity`) and a vector ranking
+/// (`TagEmbedding::embedding_similarity`) via reciprocal rank fusion, so retrieval degrades
+/// gracefully when either signal is weak rather than depending on one alone.
+pub struct HybridRetriever {
+    /// Minimum Jaccard score a candidate needs to stay in the lexical ranked list.
+    pub min_score_lexical: f64,
+    /// Minimum cosine score a candidate needs to stay in the vector ranked list.
+    pub min_score_vector: f64,
+    /// Weight applied to the lexical list's reciprocal-rank contribution.
+    pub weight_lexical: f64,
+    /// Weight applied to the vector list's reciprocal-rank contribution.
+    pub weight_vector: f64,
+}
+
+impl Default for HybridRetriever {
+    fn default() -> Self {
+        Self {
+            min_score_lexical: 0.0,
+            min_score_vector: 0.0,
+            weight_lexical: 1.0,
+            weight_vector: 1.0,
+        }
+    }
+}
+
+impl HybridRetriever {
+    /// The `c` constant in `score = w / (c + rank)`; dampens the influence of a single
+    /// list's top hit so agreement across both lists matters more than either one alone.
+    pub const RRF_C: f64 = 60.0;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_min_scores(mut self, lexical: f64, vector: f64) -> Self {
+        self.min_score_lexical = lexical;
+        self.min_score_vector = vector;
+        self
+    }
+
+    pub fn with_weights(mut self, lexical: f64, vector: f64) -> Self {
+        self.weight_lexical = lexical;
+        self.weight_vector = vector;
+        self
+    }
+
+    /// Rank `candidate_sets` against `query_tags`/`query_embedding` by fused reciprocal-rank
+    /// score, returning the top `top_k` `(index, score)` pairs. `candidate_embeddings` must
+    /// be parallel to `candidate_sets` (one embedding per tag set, same vocabulary as
+    /// `query_embedding`).
+    pub fn retrieve(
+        &self,
+        query_tags: &[String],
+        query_embedding: &[f64],
+        candidate_sets: &[Vec<String>],
+        candidate_embeddings: &[Vec<f64>],
+        top_k: usize,
+    ) -> Vec<(usize, f64)> {
+        let mut lexical_ranked: Vec<(usize, f64)> = candidate_sets
+            .iter()
+            .enumerate()
+            .map(|(idx, tags)| (idx, TagSimilarity::jaccard_similarity(query_tags, tags)))
+            .filter(|(_, score)| *score >= self.min_score_lexical)
+            .collect();
+        lexical_ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+
+        let mut vector_ranked: Vec<(usize, f64)> = candidate_embeddings
+            .iter()
+            .enumerate()
+            .map(|(idx, emb)| (idx, TagEmbedding::embedding_similarity(query_embedding, emb)))
+            .filter(|(_, score)| *score >= self.min_score_vector)
+            .collect();
+        vector_ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+
+        let mut fused_scores: HashMap<usize, f64> = HashMap::new();
+        for (rank, (idx, _)) in lexical_ranked.iter().enumerate() {
+            *fused_scores.entry(*idx).or_insert(0.0) +=
+                self.weight_lexical / (Self::RRF_C + (rank + 1) as f64);
+        }
+        for (rank, (idx, _)) in vector_ranked.iter().enumerate() {
+            *fused_scores.entry(*idx).or_insert(0.0) +=
+                self.weight_vector / (Self::RRF_C + (rank + 1) as f64);
+        }
+
+        let mut fused: Vec<(usize, f64)> = fused_scores.into_iter().collect();
+        fused.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+        fused.truncate(top_k);
+        fused
+    }
 }
 
 /// ML task results for benchmarking
@@ -286,6 +932,16 @@ pub struct MLTaskResults {
     pub approximate_time_ms: f64,
     pub accuracy_difference: f64,
     pub speed_ratio: f64,
+    /// Mean absolute error between MinHash-estimated and exact Jaccard similarity, for
+    /// tasks that compare the two (e.g. `benchmark_similarity_search_minhash`).
+    pub minhash_jaccard_error: Option<f64>,
+    /// Size of the LSH candidate set produced for a query, divided by the total number of
+    /// items searched (1.0 means no pruning happened, i.e. a fallback full scan).
+    pub candidate_set_ratio: Option<f64>,
+    /// Unique content digests stored divided by total items, for benchmarks that use
+    /// content-addressed dedup (1.0 means every tag set was unique; lower means
+    /// duplicates collapsed to fewer stored entries).
+    pub dedup_ratio: Option<f64>,
 }
 
 impl MLTaskResults {
@@ -298,7 +954,7 @@ impl MLTaskResults {
     ) -> Self {
         let accuracy_difference = (exact_accuracy - approximate_accuracy).abs();
         let speed_ratio = approximate_time_ms / exact_time_ms.max(0.001);
-        
+
         Self {
             task_name,
             exact_accuracy,
@@ -307,9 +963,30 @@ impl MLTaskResults {
             approximate_time_ms,
             accuracy_difference,
             speed_ratio,
+            minhash_jaccard_error: None,
+            candidate_set_ratio: None,
+            dedup_ratio: None,
         }
     }
-    
+
+    /// Attach a MinHash-vs-exact Jaccard error to this result.
+    pub fn with_minhash_jaccard_error(mut self, error: f64) -> Self {
+        self.minhash_jaccard_error = Some(error);
+        self
+    }
+
+    /// Attach an LSH candidate-set-size ratio to this result.
+    pub fn with_candidate_set_ratio(mut self, ratio: f64) -> Self {
+        self.candidate_set_ratio = Some(ratio);
+        self
+    }
+
+    /// Attach a content-addressed dedup ratio to this result.
+    pub fn with_dedup_ratio(mut self, ratio: f64) -> Self {
+        self.dedup_ratio = Some(ratio);
+        self
+    }
+
     /// Check if approximate results are acceptable (within threshold)
     pub fn is_acceptable(&self, accuracy_threshold: f64, speed_threshold: f64) -> bool {
         self.accuracy_difference <= accuracy_threshold && self.speed_ratio <= speed_threshold
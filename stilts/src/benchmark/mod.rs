@@ -1,9 +1,13 @@
 #![allow(clippy::cast_precision_loss)] // Acceptable for benchmark calculations
 //! Benchmarking framework for compression algorithms
 
+pub mod baseline;
+pub mod cachegrind;
 pub mod comparison;
+pub mod complexity;
 pub mod metrics;
 pub mod runner;
+pub mod stats;
 
 #[cfg(feature = "mappy-integration")]
 pub mod mappy_comparison;
@@ -16,6 +20,10 @@ pub mod ml_benchmarks;
 #[cfg(feature = "mappy-integration")]
 pub mod ml_tasks;
 
-pub use comparison::ComparisonRunner;
+pub use baseline::{compare_to_baseline, save_baseline, RegressionVerdict};
+pub use cachegrind::InstructionCounts;
+pub use comparison::{CompressionMethod, ComparisonRunner};
+pub use complexity::ComplexityEstimate;
 pub use metrics::{BenchmarkMetrics, CompressionStats};
 pub use runner::BenchmarkRunner;
+pub use stats::SampleStats;
@@ -2,14 +2,20 @@
 
 use std::time::Instant;
 use anyhow::Result;
-use crate::compression::{Compressor, HuffmanCompressor, ArithmeticCompressor, DictionaryCompressor};
+use crate::compression::{Compressor, HuffmanCompressor, ArithmeticCompressor, DictionaryCompressor, FsstCompressor};
+use crate::benchmark::cachegrind;
+use crate::benchmark::complexity;
 use crate::benchmark::metrics::{BenchmarkMetrics, CompressionStats};
+use crate::benchmark::stats;
 
 /// Benchmark runner
 pub struct BenchmarkRunner;
 
 impl BenchmarkRunner {
-    /// Benchmark a compressor
+    /// Benchmark a compressor, collecting each iteration's compression/decompression time
+    /// individually (rather than only their total) so `CompressionStats` can report mean,
+    /// median, standard deviation, a bootstrapped confidence interval, and any Tukey-fence
+    /// outliers instead of a single averaged number a scheduler hiccup could skew.
     pub fn benchmark<C: Compressor>(
         compressor: &C,
         tags: &[String],
@@ -22,45 +28,153 @@ impl BenchmarkRunner {
             original_bytes.push(b' ');
         }
         let original_size = original_bytes.len();
-        
+
         // Warmup
         for _ in 0..3 {
             let _ = compressor.compress(tags);
         }
-        
-        // Benchmark compression
-        let start = Instant::now();
+
+        // Benchmark compression, one sample per iteration
         let mut compressed_data = Vec::new();
+        let mut compression_samples_ms = Vec::with_capacity(iterations);
         for _ in 0..iterations {
+            let start = Instant::now();
             compressed_data = compressor.compress(tags)?;
+            compression_samples_ms.push(start.elapsed().as_secs_f64() * 1000.0);
         }
-        let compression_time = start.elapsed().as_secs_f64() * 1000.0 / iterations as f64;
-        
-        // Benchmark decompression
-        let start = Instant::now();
+
+        // Benchmark decompression, one sample per iteration
+        let mut decompression_samples_ms = Vec::with_capacity(iterations);
         for _ in 0..iterations {
+            let start = Instant::now();
             let _ = compressor.decompress(&compressed_data)?;
+            decompression_samples_ms.push(start.elapsed().as_secs_f64() * 1000.0);
         }
-        let decompression_time = start.elapsed().as_secs_f64() * 1000.0 / iterations as f64;
-        
+
+        let compression_time_stats = stats::compute_sample_stats(&compression_samples_ms);
+        let decompression_time_stats = stats::compute_sample_stats(&decompression_samples_ms);
+
         let compressed_size = compressed_data.len();
         let dictionary_size = 0; // TODO: Get actual dictionary size
-        
-        let stats = CompressionStats::new(
+
+        let mut stats = CompressionStats::new(
             original_size,
             compressed_size,
-            compression_time,
-            decompression_time,
+            compression_time_stats.mean,
+            decompression_time_stats.mean,
             dictionary_size,
         );
-        
+        stats.compression_time_stats = Some(compression_time_stats);
+        stats.decompression_time_stats = Some(decompression_time_stats);
+
         Ok(BenchmarkMetrics {
             algorithm: compressor.algorithm_name().to_string(),
             stats,
             memory_usage_bytes: compressed_size,
+            complexity: None,
         })
     }
     
+    /// Benchmark a compressor by retired instruction count under Cachegrind instead of
+    /// wall-clock time, so the result is exactly reproducible across runs and machines — a
+    /// single shot is enough, unlike the iteration-averaged timing `benchmark` needs to smooth
+    /// out scheduler noise. Falls back to `benchmark`'s timing-based measurement when not
+    /// already running under Valgrind and re-exec'ing into it fails (Valgrind isn't installed,
+    /// or the `cachegrind` feature is disabled).
+    pub fn benchmark_deterministic<C: Compressor>(
+        compressor: &C,
+        tags: &[String],
+    ) -> Result<BenchmarkMetrics> {
+        let mut original_bytes = Vec::new();
+        for tag in tags {
+            original_bytes.extend_from_slice(tag.as_bytes());
+            original_bytes.push(b' ');
+        }
+
+        if cachegrind::running_under_valgrind() {
+            // This is the re-exec'd child being measured: just run the workload and return.
+            // Cachegrind only finalizes `out_path`'s summary line once this process exits, so
+            // parsing it here — instead of in the parent, after `reexec_under_cachegrind`
+            // returns — would read a nonexistent or incomplete file.
+            let compressed = compressor.compress(tags)?;
+            let _ = compressor.decompress(&compressed)?;
+
+            let stats = CompressionStats::new(original_bytes.len(), compressed.len(), 0.0, 0.0, 0);
+            return Ok(BenchmarkMetrics {
+                algorithm: compressor.algorithm_name().to_string(),
+                stats,
+                memory_usage_bytes: compressed.len(),
+                complexity: None,
+            });
+        }
+
+        let out_path = format!("cachegrind.out.{}", compressor.algorithm_name());
+        if cachegrind::reexec_under_cachegrind(&out_path).is_err() {
+            return Self::benchmark(compressor, tags, 10);
+        }
+
+        // The child (re-exec'd above) has exited by now, so `out_path` is complete.
+        let compressed_size = compressor.compress(tags)?.len();
+        let counts = cachegrind::parse_cachegrind_output(&out_path)?;
+
+        let mut stats = CompressionStats::new(original_bytes.len(), compressed_size, 0.0, 0.0, 0);
+        stats.instruction_counts = Some(counts);
+
+        Ok(BenchmarkMetrics {
+            algorithm: compressor.algorithm_name().to_string(),
+            stats,
+            memory_usage_bytes: compressed_size,
+            complexity: None,
+        })
+    }
+
+    /// Benchmark a compressor across a geometric sweep of corpus sizes (`2^1, 2^2, ..., 2^max_power`
+    /// tags, capped at `corpus.len()`), fit the resulting `(size, time)` curve against the
+    /// candidate complexity models in `complexity::fit_complexity`, and attach the winning
+    /// model to the full-corpus benchmark's `complexity` field. `build` trains a fresh
+    /// compressor on each size's prefix of `corpus`, since backends like Huffman and FSST carry
+    /// corpus-sized state that a single trained instance can't be re-timed at a smaller size.
+    pub fn benchmark_complexity<C, F>(
+        build: F,
+        corpus: &[String],
+        max_power: u32,
+        iterations: usize,
+    ) -> Result<BenchmarkMetrics>
+    where
+        C: Compressor,
+        F: Fn(&[String]) -> Result<C>,
+    {
+        let mut samples = Vec::new();
+        for power in 1..=max_power {
+            let size = (1usize << power).min(corpus.len());
+            if size == 0 {
+                continue;
+            }
+            let slice = &corpus[..size];
+            let compressor = build(slice)?;
+
+            for _ in 0..3 {
+                let _ = compressor.compress(slice);
+            }
+
+            let start = Instant::now();
+            for _ in 0..iterations {
+                compressor.compress(slice)?;
+            }
+            let time_ms = start.elapsed().as_secs_f64() * 1000.0 / iterations as f64;
+
+            samples.push((size as f64, time_ms));
+        }
+
+        let estimate = complexity::fit_complexity(&samples);
+
+        let full_corpus_compressor = build(corpus)?;
+        let mut metrics = Self::benchmark(&full_corpus_compressor, corpus, iterations)?;
+        metrics.complexity = Some(estimate);
+
+        Ok(metrics)
+    }
+
     /// Benchmark all algorithms
     pub fn benchmark_all(tags: &[String], iterations: usize) -> Result<Vec<BenchmarkMetrics>> {
         let mut results = Vec::new();
@@ -79,8 +193,35 @@ impl BenchmarkRunner {
         let mut dictionary = DictionaryCompressor::new();
         dictionary.build_from_corpus(tags)?;
         results.push(Self::benchmark(&dictionary, tags, iterations)?);
-        
+
+        // FSST
+        let mut fsst = FsstCompressor::new();
+        fsst.train(tags)?;
+        results.push(Self::benchmark(&fsst, tags, iterations)?);
+
         Ok(results)
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compression::HuffmanCompressor;
+
+    #[test]
+    fn test_benchmark_deterministic_falls_back_to_wall_clock_without_valgrind() {
+        // Without the `cachegrind` feature (and thus without Valgrind installed from this
+        // process's point of view), `reexec_under_cachegrind` always errors, so this should
+        // fall through to `benchmark`'s ordinary timing-based measurement rather than hang or
+        // error out itself.
+        let tags = vec!["a".to_string(), "b".to_string(), "a".to_string()];
+        let mut compressor = HuffmanCompressor::new();
+        compressor.build_from_corpus(&tags).unwrap();
+
+        let metrics = BenchmarkRunner::benchmark_deterministic(&compressor, &tags).unwrap();
+
+        assert_eq!(metrics.algorithm, "huffman");
+        assert!(metrics.stats.instruction_counts.is_none());
+    }
+}
+
@@ -0,0 +1,138 @@
+#![allow(clippy::cast_precision_loss)] // Acceptable for statistical calculations
+//! Statistical summaries of per-iteration benchmark samples.
+//!
+//! `BenchmarkRunner::benchmark` used to collapse every iteration into a single arithmetic mean,
+//! throwing away the distribution a noisy scheduler spike might show up in. `compute_sample_stats`
+//! keeps the full sample set just long enough to report mean, median, standard deviation, a
+//! bootstrapped 95% confidence interval, and which samples (if any) the Tukey fence flags as
+//! outliers.
+
+use serde::{Deserialize, Serialize};
+
+/// Summary statistics for a set of per-iteration timing samples.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SampleStats {
+    pub mean: f64,
+    pub median: f64,
+    pub stddev: f64,
+    /// Bootstrapped 95% confidence interval for the mean, as `(lower, upper)`.
+    pub confidence_interval_95: (f64, f64),
+    /// Samples falling outside the Tukey fence (1.5x the interquartile range beyond Q1/Q3).
+    pub outliers: Vec<f64>,
+}
+
+/// Deterministic (not OS-random) bootstrap resampling, seeded with a fixed constant via
+/// `SplitMix64` so repeated runs over the same samples always produce the same confidence
+/// interval.
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+const BOOTSTRAP_RESAMPLES: usize = 1000;
+
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = (p * (sorted.len() - 1) as f64).round() as usize;
+    sorted[rank.min(sorted.len() - 1)]
+}
+
+/// Bootstrap a 95% confidence interval for the mean of `samples` by resampling with
+/// replacement `BOOTSTRAP_RESAMPLES` times and taking the 2.5th/97.5th percentile of the
+/// resampled means.
+fn bootstrap_confidence_interval(samples: &[f64]) -> (f64, f64) {
+    if samples.is_empty() {
+        return (0.0, 0.0);
+    }
+
+    let mut state = 0x5EED_u64;
+    let mut resampled_means: Vec<f64> = (0..BOOTSTRAP_RESAMPLES)
+        .map(|_| {
+            let sum: f64 = (0..samples.len())
+                .map(|_| samples[(splitmix64(&mut state) as usize) % samples.len()])
+                .sum();
+            sum / samples.len() as f64
+        })
+        .collect();
+
+    resampled_means.sort_by(|a, b| a.partial_cmp(b).expect("resampled means are never NaN"));
+    (percentile(&resampled_means, 0.025), percentile(&resampled_means, 0.975))
+}
+
+/// Compute mean/median/stddev/confidence-interval/outliers for `samples`.
+///
+/// # Panics
+/// Panics if `samples` is empty.
+pub fn compute_sample_stats(samples: &[f64]) -> SampleStats {
+    assert!(!samples.is_empty(), "compute_sample_stats requires at least one sample");
+
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).expect("samples are never NaN"));
+
+    let n = sorted.len();
+    let mean = sorted.iter().sum::<f64>() / n as f64;
+
+    let median = if n % 2 == 0 {
+        (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0
+    } else {
+        sorted[n / 2]
+    };
+
+    let stddev = if n > 1 {
+        let variance = sorted.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1) as f64;
+        variance.sqrt()
+    } else {
+        0.0
+    };
+
+    let q1 = percentile(&sorted, 0.25);
+    let q3 = percentile(&sorted, 0.75);
+    let iqr = q3 - q1;
+    let lower_fence = q1 - 1.5 * iqr;
+    let upper_fence = q3 + 1.5 * iqr;
+    let outliers: Vec<f64> = sorted
+        .iter()
+        .filter(|&&v| v < lower_fence || v > upper_fence)
+        .copied()
+        .collect();
+
+    let confidence_interval_95 = bootstrap_confidence_interval(&sorted);
+
+    SampleStats { mean, median, stddev, confidence_interval_95, outliers }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_sample_stats_on_uniform_samples() {
+        let samples = vec![10.0, 10.0, 10.0, 10.0, 10.0];
+        let stats = compute_sample_stats(&samples);
+        assert!((stats.mean - 10.0).abs() < 1e-9);
+        assert!((stats.median - 10.0).abs() < 1e-9);
+        assert!((stats.stddev).abs() < 1e-9);
+        assert!(stats.outliers.is_empty());
+    }
+
+    #[test]
+    fn test_compute_sample_stats_flags_tukey_fence_outlier() {
+        let mut samples = vec![10.0, 11.0, 10.5, 9.5, 10.2, 9.8, 10.1];
+        samples.push(1000.0);
+        let stats = compute_sample_stats(&samples);
+        assert!(stats.outliers.contains(&1000.0));
+    }
+
+    #[test]
+    fn test_bootstrap_confidence_interval_contains_mean() {
+        let samples = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+        let stats = compute_sample_stats(&samples);
+        assert!(stats.confidence_interval_95.0 <= stats.mean);
+        assert!(stats.confidence_interval_95.1 >= stats.mean);
+    }
+}
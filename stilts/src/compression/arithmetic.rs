@@ -1,138 +1,347 @@
 //! Arithmetic coding compression implementation
+//!
+//! A byte-oriented integer range coder (the carryless variant: Subbotin's trick of
+//! shrinking `range` to avoid the carry-propagation case instead of buffering pending
+//! output bytes) over a per-tag frequency table, so `compress`/`decompress` round-trip
+//! losslessly regardless of message length — unlike collapsing the whole message into one
+//! `f64` midpoint, which loses precision after a few dozen symbols.
 
 use std::collections::HashMap;
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use crate::compression::Compressor;
 
+/// Renormalize once `low`/`low+range` agree on their top byte, i.e. once their XOR drops
+/// below this. `BOTTOM` sets when agreement is forced instead.
+const TOP: u32 = 1 << 24;
+/// Floor under which `range` is forced back up (via the carryless trick) rather than left
+/// to underflow.
+const BOTTOM: u32 = 1 << 16;
+/// The largest cumulative frequency total `CumulativeTable::from_frequencies` will build.
+/// `RangeEncoder::encode`/`RangeDecoder::get_freq` both divide `range` by `total`; once
+/// `total` exceeds `BOTTOM`, that division can round to 0 while `range` is pinned near
+/// `BOTTOM`, which leaves `normalize`'s loop condition permanently true (an infinite loop on
+/// otherwise valid input). Kept at half of `BOTTOM` for headroom. Every distinct tag needs a
+/// cumulative frequency of at least 1 to stay representable, so this is also a hard ceiling
+/// on distinct tag count: `from_frequencies` rejects a corpus with more tags than this, since
+/// no rescale can fit them under the total without losing tags entirely.
+const MAX_TOTAL_FREQUENCY: u32 = BOTTOM / 2;
+
 /// Arithmetic coding compressor
 pub struct ArithmeticCompressor {
-    probabilities: HashMap<String, f64>,
+    /// Per-tag occurrence counts from the training corpus, used to build a cumulative
+    /// frequency table identically on encode and decode.
+    frequencies: HashMap<String, u32>,
 }
 
 impl ArithmeticCompressor {
     /// Create a new arithmetic compressor
     pub fn new() -> Self {
         Self {
-            probabilities: HashMap::new(),
+            frequencies: HashMap::new(),
         }
     }
-    
-    /// Build probability model from corpus
+
+    /// Build frequency model from corpus
     pub fn build_from_corpus(&mut self, corpus: &[String]) -> Result<()> {
-        let total = corpus.len() as f64;
-        if total == 0.0 {
-            anyhow::bail!("Cannot build model from empty corpus");
+        if corpus.is_empty() {
+            bail!("Cannot build model from empty corpus");
         }
-        
-        // Count frequencies
-        let mut frequencies = HashMap::new();
+
+        self.frequencies.clear();
         for tag in corpus {
-            *frequencies.entry(tag.clone()).or_insert(0) += 1;
+            *self.frequencies.entry(tag.clone()).or_insert(0) += 1;
         }
-        
-        // Calculate probabilities
-        self.probabilities.clear();
-        for (tag, count) in frequencies {
-            self.probabilities.insert(tag, count as f64 / total);
-        }
-        
+
         Ok(())
     }
-    
-    fn get_cumulative_ranges(&self, tags: &[String]) -> Vec<(f64, f64)> {
-        let mut ranges = Vec::new();
-        let mut cumulative = 0.0;
-        
-        for tag in tags {
-            let prob = self.probabilities.get(tag).copied().unwrap_or(1e-10);
-            let start = cumulative;
-            cumulative += prob;
-            ranges.push((start, cumulative));
-        }
-        
-        ranges
-    }
-    
+
     fn encode_arithmetic(&self, tags: &[String]) -> Result<Vec<u8>> {
         if tags.is_empty() {
             return Ok(Vec::new());
         }
-        
-        // Build probability model if not already built
-        let mut compressor = self.clone();
-        compressor.build_from_corpus(tags)?;
-        
-        // Get cumulative ranges
-        let ranges = compressor.get_cumulative_ranges(tags);
-        
-        // Encode using arithmetic coding (simplified version)
-        let mut low = 0.0;
-        let mut high = 1.0;
-        
-        for (start, end) in ranges {
-            let range = high - low;
-            high = low + range * end;
-            low += range * start;
-        }
-        
-        // Convert to fixed-point representation (32-bit)
-        let value = (low + high) / 2.0;
-        let encoded = (value * (u32::MAX as f64)) as u32;
-        
-        // Serialize: count (u32) + value (u32) + probabilities
-        let mut result = Vec::new();
+
+        let table = CumulativeTable::from_frequencies(&self.frequencies)?;
+
+        let mut encoder = RangeEncoder::new();
+        for tag in tags {
+            let index = table
+                .index_of(tag)
+                .with_context(|| format!("Tag not found in frequency table: {}", tag))?;
+            let (cum_low, cum_high) = table.range_for(index);
+            encoder.encode(cum_low, cum_high - cum_low, table.total);
+        }
+        let encoded = encoder.finish();
+
+        let mut result = vec![crate::compression::CompressionMethod::Arithmetic.id()];
         result.extend_from_slice(&(tags.len() as u32).to_le_bytes());
-        result.extend_from_slice(&encoded.to_le_bytes());
-        
-        // Store probabilities for decoding
-        let prob_data = bincode::encode_to_vec(&compressor.probabilities, bincode::config::standard())?;
-        result.extend_from_slice(&(prob_data.len() as u32).to_le_bytes());
-        result.extend_from_slice(&prob_data);
-        
+
+        let freq_data = bincode::encode_to_vec(&self.frequencies, bincode::config::standard())?;
+        result.extend_from_slice(&(freq_data.len() as u32).to_le_bytes());
+        result.extend_from_slice(&freq_data);
+
+        result.extend_from_slice(&(encoded.len() as u32).to_le_bytes());
+        result.extend_from_slice(&encoded);
+
         Ok(result)
     }
-    
+
     fn decode_arithmetic(&self, data: &[u8]) -> Result<Vec<String>> {
-        if data.len() < 8 {
-            anyhow::bail!("Insufficient data");
+        if data.is_empty() {
+            return Ok(Vec::new());
+        }
+        let data = data.split_first().context("Empty arithmetic blob")?.1;
+        if data.len() < 4 {
+            bail!("Insufficient data for tag count");
         }
-        
-        // Decode count
+
         let count = u32::from_le_bytes(data[0..4].try_into().unwrap()) as usize;
-        
-        // Decode value (currently unused but kept for future use)
-        let _encoded = u32::from_le_bytes(data[4..8].try_into().unwrap());
-        let _value = _encoded as f64 / (u32::MAX as f64);
-        
-        // Decode probabilities
-        let prob_len = u32::from_le_bytes(data[8..12].try_into().unwrap()) as usize;
-        if data.len() < 12 + prob_len {
-            anyhow::bail!("Insufficient data for probabilities");
-        }
-        let (probabilities, _): (HashMap<String, f64>, _) = bincode::decode_from_slice(&data[12..12+prob_len], bincode::config::standard())?;
-        
-        // Decode tags (simplified - in practice, need proper arithmetic decoding)
-        // For now, return empty as this is a simplified implementation
-        let mut result = Vec::new();
-        
-        // Build tag list from probabilities (simplified decoding)
-        let mut sorted_tags: Vec<_> = probabilities.keys().collect();
-        sorted_tags.sort();
-        
-        for _ in 0..count.min(sorted_tags.len()) {
-            if let Some(tag) = sorted_tags.first() {
-                result.push((*tag).clone());
-            }
+        let mut pos = 4;
+
+        let freq_len_bytes = data
+            .get(pos..pos + 4)
+            .context("Insufficient data for frequency table length")?;
+        let freq_len = u32::from_le_bytes(freq_len_bytes.try_into().unwrap()) as usize;
+        pos += 4;
+        let freq_bytes = data.get(pos..pos + freq_len).context("Truncated frequency table")?;
+        pos += freq_len;
+        let (frequencies, _): (HashMap<String, u32>, _) =
+            bincode::decode_from_slice(freq_bytes, bincode::config::standard())?;
+
+        let encoded_len_bytes = data
+            .get(pos..pos + 4)
+            .context("Insufficient data for encoded payload length")?;
+        let encoded_len = u32::from_le_bytes(encoded_len_bytes.try_into().unwrap()) as usize;
+        pos += 4;
+        let encoded = data.get(pos..pos + encoded_len).context("Truncated encoded payload")?;
+
+        let table = CumulativeTable::from_frequencies(&frequencies)?;
+        if table.total == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut decoder = RangeDecoder::new(encoded);
+        let mut result = Vec::with_capacity(count);
+        for _ in 0..count {
+            let target = decoder.get_freq(table.total);
+            let index = table.symbol_for_target(target);
+            let (cum_low, cum_high) = table.range_for(index);
+            decoder.decode_update(cum_low, cum_high - cum_low);
+            result.push(table.tags[index].clone());
         }
-        
+
         Ok(result)
     }
 }
 
+/// A cumulative frequency table built deterministically from a frequency map: entries are
+/// sorted by tag so the encoder and a decoder given the same frequency map always rebuild
+/// the identical table and symbol ordering.
+struct CumulativeTable {
+    tags: Vec<String>,
+    index_of: HashMap<String, usize>,
+    /// `cum_freq[i]` is the sum of frequencies of `tags[0..i]`; length is `tags.len() + 1`.
+    cum_freq: Vec<u32>,
+    total: u32,
+}
+
+impl CumulativeTable {
+    fn from_frequencies(frequencies: &HashMap<String, u32>) -> Result<Self> {
+        let mut entries: Vec<(&String, &u32)> = frequencies.iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+
+        // Every distinct tag needs cumulative frequency >= 1 to stay representable, so a
+        // corpus with more distinct tags than the total budget can never be rescaled to
+        // fit — not even by flooring every tag down to the minimum.
+        if entries.len() as u32 > MAX_TOTAL_FREQUENCY {
+            bail!(
+                "corpus has {} distinct tags, more than the arithmetic coder's limit of {} \
+                 (each tag needs cumulative frequency >= 1, so no rescale can fit them under \
+                 the range coder's total budget)",
+                entries.len(),
+                MAX_TOTAL_FREQUENCY
+            );
+        }
+
+        let raw_total: u64 = entries.iter().map(|&(_, &freq)| freq as u64).sum();
+        let needs_rescale = raw_total > MAX_TOTAL_FREQUENCY as u64;
+
+        let mut tags = Vec::with_capacity(entries.len());
+        let mut index_of = HashMap::with_capacity(entries.len());
+        let mut scaled = Vec::with_capacity(entries.len());
+
+        for (tag, &freq) in &entries {
+            // Proportionally rescale each count (never below 1, so every tag stays
+            // representable) rather than leaving `total` unbounded — see `MAX_TOTAL_FREQUENCY`.
+            let freq = if needs_rescale {
+                (((freq as u64) * MAX_TOTAL_FREQUENCY as u64) / raw_total).max(1) as u32
+            } else {
+                freq
+            };
+            index_of.insert((*tag).clone(), tags.len());
+            tags.push((*tag).clone());
+            scaled.push(freq);
+        }
+
+        // The `.max(1)` floor above can re-inflate the rescaled total back over
+        // `MAX_TOTAL_FREQUENCY`: every tag whose true proportional share rounded down to 0
+        // gets bumped back up to 1, and with enough such tags (or one very dominant tag
+        // soaking up most of the budget) that alone can push the sum past the limit again.
+        // Trim the excess back off the largest frequencies one unit at a time — bounded
+        // (`entries.len() <= MAX_TOTAL_FREQUENCY` is already enforced above, and every tag
+        // floors to at least 1, so the sum can never be trimmed below `entries.len()`) and
+        // only nudges the model slightly rather than breaking the total<=MAX_TOTAL_FREQUENCY
+        // invariant `normalize` depends on.
+        if needs_rescale {
+            let mut total: u64 = scaled.iter().map(|&f| f as u64).sum();
+            if total > MAX_TOTAL_FREQUENCY as u64 {
+                let mut order: Vec<usize> = (0..scaled.len()).collect();
+                order.sort_by_key(|&i| std::cmp::Reverse(scaled[i]));
+                let mut cursor = 0;
+                while total > MAX_TOTAL_FREQUENCY as u64 {
+                    let i = order[cursor % order.len()];
+                    if scaled[i] > 1 {
+                        scaled[i] -= 1;
+                        total -= 1;
+                    }
+                    cursor += 1;
+                }
+            }
+        }
+
+        let mut cum_freq = Vec::with_capacity(scaled.len() + 1);
+        let mut total = 0u32;
+        cum_freq.push(0);
+        for freq in scaled {
+            total += freq;
+            cum_freq.push(total);
+        }
+
+        Ok(Self { tags, index_of, cum_freq, total })
+    }
+
+    fn index_of(&self, tag: &str) -> Option<usize> {
+        self.index_of.get(tag).copied()
+    }
+
+    fn range_for(&self, index: usize) -> (u32, u32) {
+        (self.cum_freq[index], self.cum_freq[index + 1])
+    }
+
+    /// The symbol whose `[cum_low, cum_high)` interval contains `target`.
+    fn symbol_for_target(&self, target: u32) -> usize {
+        self.cum_freq.partition_point(|&c| c <= target) - 1
+    }
+}
+
+/// Encodes symbols into a carryless range coder's `low`/`range` state, flushing bytes as
+/// `range` narrows past `TOP`/`BOTTOM` rather than buffering every pending output byte
+/// across a potential carry.
+struct RangeEncoder {
+    low: u32,
+    range: u32,
+    out: Vec<u8>,
+}
+
+impl RangeEncoder {
+    fn new() -> Self {
+        Self { low: 0, range: u32::MAX, out: Vec::new() }
+    }
+
+    fn encode(&mut self, cum_low: u32, freq: u32, total: u32) {
+        let r = self.range / total;
+        self.low = self.low.wrapping_add(r * cum_low);
+        self.range = r * freq;
+        self.normalize();
+    }
+
+    /// Emit the top byte of `low` and shift both registers left by 8 bits whenever `low`
+    /// and `low + range` already agree on their top byte (no further narrowing can change
+    /// it), or `range` has dropped below `BOTTOM` (shrunk here instead, to force agreement
+    /// rather than risk a carry out of the top byte on a later `encode`).
+    fn normalize(&mut self) {
+        while (self.low ^ self.low.wrapping_add(self.range)) < TOP
+            || (self.range < BOTTOM && {
+                self.range = self.low.wrapping_neg() & (BOTTOM - 1);
+                true
+            })
+        {
+            self.out.push((self.low >> 24) as u8);
+            self.low <<= 8;
+            self.range <<= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        for _ in 0..4 {
+            self.out.push((self.low >> 24) as u8);
+            self.low <<= 8;
+        }
+        self.out
+    }
+}
+
+/// Mirrors `RangeEncoder`, maintaining a `code` register read from the byte stream instead
+/// of accumulating output.
+struct RangeDecoder<'a> {
+    low: u32,
+    range: u32,
+    code: u32,
+    data: &'a [u8],
+    pos: usize,
+    /// `range / total` from the most recent `get_freq`, reused by `decode_update` so the
+    /// two steps divide by `total` exactly once between them, matching the encoder.
+    last_r: u32,
+}
+
+impl<'a> RangeDecoder<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        let mut code = 0u32;
+        let mut pos = 0;
+        for _ in 0..4 {
+            code = (code << 8) | data.get(pos).copied().unwrap_or(0) as u32;
+            pos += 1;
+        }
+        Self { low: 0, range: u32::MAX, code, data, pos, last_r: 1 }
+    }
+
+    /// The cumulative-frequency position the current code value falls at, clamped to
+    /// `total - 1` so rounding in the division never points past the last symbol.
+    fn get_freq(&mut self, total: u32) -> u32 {
+        self.last_r = (self.range / total).max(1);
+        let target = self.code.wrapping_sub(self.low) / self.last_r;
+        target.min(total - 1)
+    }
+
+    fn decode_update(&mut self, cum_low: u32, freq: u32) {
+        self.low = self.low.wrapping_add(self.last_r * cum_low);
+        self.range = self.last_r * freq;
+        self.normalize();
+    }
+
+    fn normalize(&mut self) {
+        while (self.low ^ self.low.wrapping_add(self.range)) < TOP
+            || (self.range < BOTTOM && {
+                self.range = self.low.wrapping_neg() & (BOTTOM - 1);
+                true
+            })
+        {
+            self.code = (self.code << 8) | self.next_byte() as u32;
+            self.low <<= 8;
+            self.range <<= 8;
+        }
+    }
+
+    fn next_byte(&mut self) -> u8 {
+        let b = self.data.get(self.pos).copied().unwrap_or(0);
+        self.pos += 1;
+        b
+    }
+}
+
 impl Clone for ArithmeticCompressor {
     fn clone(&self) -> Self {
         Self {
-            probabilities: self.probabilities.clone(),
+            frequencies: self.frequencies.clone(),
         }
     }
 }
@@ -145,46 +354,169 @@ impl Default for ArithmeticCompressor {
 
 impl Compressor for ArithmeticCompressor {
     fn compress(&self, tags: &[String]) -> Result<Vec<u8>> {
-        // If probabilities are already built, use them; otherwise build them
-        let compressor = if self.probabilities.is_empty() {
+        // If frequencies are already built, use them; otherwise build them
+        let compressor = if self.frequencies.is_empty() && !tags.is_empty() {
             let mut new_compressor = self.clone();
             new_compressor.build_from_corpus(tags)?;
             new_compressor
         } else {
-            // Probabilities already built, use existing
+            // Frequencies already built, use existing
             self.clone()
         };
-        
+
         compressor.encode_arithmetic(tags)
     }
-    
+
     fn decompress(&self, data: &[u8]) -> Result<Vec<String>> {
         self.decode_arithmetic(data)
     }
-    
+
     fn algorithm_name(&self) -> &'static str {
         "arithmetic"
     }
+
+    fn save_model(&self) -> Result<Vec<u8>> {
+        Ok(bincode::encode_to_vec(&self.frequencies, bincode::config::standard())?)
+    }
+
+    fn load_model(&mut self, data: &[u8]) -> Result<()> {
+        let (frequencies, _): (HashMap<String, u32>, _) =
+            bincode::decode_from_slice(data, bincode::config::standard())?;
+        self.frequencies = frequencies;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    
+
     #[test]
-    fn test_arithmetic_basic() {
+    fn test_arithmetic_round_trip() {
         let tags = vec![
             "tag1".to_string(),
             "tag2".to_string(),
             "tag1".to_string(),
         ];
-        
+
         let mut compressor = ArithmeticCompressor::new();
         compressor.build_from_corpus(&tags).unwrap();
-        
+
         let compressed = compressor.compress(&tags).unwrap();
-        // Note: Arithmetic decoding is simplified, so full round-trip may not work perfectly
-        assert!(!compressed.is_empty());
+        let decompressed = compressor.decompress(&compressed).unwrap();
+
+        assert_eq!(tags, decompressed);
+    }
+
+    #[test]
+    fn test_arithmetic_round_trip_long_sequence() {
+        // Long enough that the old f64-midpoint encoding would have lost precision.
+        let tags: Vec<String> = (0..500).map(|i| format!("tag{}", i % 7)).collect();
+
+        let mut compressor = ArithmeticCompressor::new();
+        compressor.build_from_corpus(&tags).unwrap();
+
+        let compressed = compressor.compress(&tags).unwrap();
+        let decompressed = compressor.decompress(&compressed).unwrap();
+
+        assert_eq!(tags, decompressed);
+    }
+
+    #[test]
+    fn test_arithmetic_round_trip_corpus_exceeding_bottom_threshold() {
+        // `BOTTOM` is `1 << 16`; this corpus's raw occurrence count is roughly triple that,
+        // which used to make `range / total` round to 0 and spin `RangeEncoder::normalize`
+        // forever before frequencies were rescaled in `CumulativeTable::from_frequencies`.
+        let tags: Vec<String> = (0..(3 * (1u32 << 16))).map(|i| format!("tag{}", i % 5)).collect();
+
+        let mut compressor = ArithmeticCompressor::new();
+        compressor.build_from_corpus(&tags).unwrap();
+
+        let compressed = compressor.compress(&tags).unwrap();
+        let decompressed = compressor.decompress(&compressed).unwrap();
+
+        assert_eq!(tags, decompressed);
+    }
+
+    #[test]
+    fn test_arithmetic_round_trip_skewed_high_cardinality_corpus() {
+        // Regression test for the rescale floor re-inflating `total` past
+        // `MAX_TOTAL_FREQUENCY`: a corpus with cardinality right up against the limit, but
+        // one wildly dominant tag, used to rescale every minor tag's near-zero share up to
+        // the `.max(1)` floor and push the rescaled total back over budget — hanging
+        // `normalize` exactly like the unrescaled case the earlier fix addressed. Built via
+        // `load_model` instead of `build_from_corpus` so the test doesn't need a
+        // million-element corpus to reach a million-occurrence tag.
+        let mut frequencies: HashMap<String, u32> = HashMap::new();
+        for i in 0..20_000u32 {
+            frequencies.insert(format!("minor{}", i), 1);
+        }
+        frequencies.insert("dominant".to_string(), 1_000_000);
+        let model = bincode::encode_to_vec(&frequencies, bincode::config::standard()).unwrap();
+
+        let mut compressor = ArithmeticCompressor::new();
+        compressor.load_model(&model).unwrap();
+
+        let tags = vec![
+            "dominant".to_string(),
+            "minor5".to_string(),
+            "dominant".to_string(),
+        ];
+        let compressed = compressor.compress(&tags).unwrap();
+        let decompressed = compressor.decompress(&compressed).unwrap();
+
+        assert_eq!(tags, decompressed);
+    }
+
+    #[test]
+    fn test_arithmetic_rejects_corpus_with_too_many_distinct_tags() {
+        // No rescale can fit more distinct tags than `MAX_TOTAL_FREQUENCY`, since each one
+        // needs cumulative frequency >= 1; this must fail cleanly instead of ever reaching
+        // `normalize`.
+        let mut frequencies: HashMap<String, u32> = HashMap::new();
+        for i in 0..=MAX_TOTAL_FREQUENCY {
+            frequencies.insert(format!("tag{}", i), 1);
+        }
+        let model = bincode::encode_to_vec(&frequencies, bincode::config::standard()).unwrap();
+
+        let mut compressor = ArithmeticCompressor::new();
+        compressor.load_model(&model).unwrap();
+
+        let err = compressor.compress(&["tag0".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("distinct tags"));
+    }
+
+    #[test]
+    fn test_arithmetic_compress_builds_corpus_when_untrained() {
+        let tags = vec!["a".to_string(), "b".to_string(), "a".to_string()];
+        let compressor = ArithmeticCompressor::new();
+
+        let compressed = compressor.compress(&tags).unwrap();
+        let decompressed = compressor.decompress(&compressed).unwrap();
+
+        assert_eq!(tags, decompressed);
     }
-}
 
+    #[test]
+    fn test_arithmetic_empty_tags_round_trip() {
+        let compressor = ArithmeticCompressor::new();
+        let compressed = compressor.compress(&[]).unwrap();
+        let decompressed = compressor.decompress(&compressed).unwrap();
+        assert!(decompressed.is_empty());
+    }
+
+    #[test]
+    fn test_save_and_load_model_round_trips() {
+        let tags = vec!["tag1".to_string(), "tag2".to_string(), "tag1".to_string()];
+
+        let mut trained = ArithmeticCompressor::new();
+        trained.build_from_corpus(&tags).unwrap();
+        let model = trained.save_model().unwrap();
+
+        let mut restored = ArithmeticCompressor::new();
+        restored.load_model(&model).unwrap();
+
+        let compressed = restored.compress(&tags).unwrap();
+        assert_eq!(restored.decompress(&compressed).unwrap(), tags);
+    }
+}
@@ -0,0 +1,94 @@
+//! Bloom filter
+//!
+//! A probabilistic, no-false-negative set-membership structure backing
+//! `MappyTagStorage::contains_tag`: it answers whether a compressed tag blob might contain a
+//! given tag without decompressing the blob. False positives are possible (at a rate governed
+//! by the `fp_rate` a filter was built with); false negatives never are.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+pub struct BloomFilter {
+    bits: Vec<bool>,
+    num_hashes: u32,
+}
+
+impl BloomFilter {
+    /// A filter sized for `expected_items` entries at approximately `fp_rate` false-positive
+    /// probability, using the standard optimal-bit-count/optimal-hash-count formulas.
+    pub fn new(expected_items: usize, fp_rate: f64) -> Self {
+        let expected_items = expected_items.max(1);
+        let num_bits = (-(expected_items as f64) * fp_rate.ln() / (2f64.ln().powi(2))).ceil();
+        let num_bits = (num_bits as usize).max(8);
+        let num_hashes = ((num_bits as f64 / expected_items as f64) * 2f64.ln())
+            .round()
+            .max(1.0) as u32;
+        Self {
+            bits: vec![false; num_bits],
+            num_hashes,
+        }
+    }
+
+    /// Two independent hashes of `value`, combined via double hashing (`h1 + i*h2`) below to
+    /// simulate `num_hashes` independent hash functions from just these two.
+    fn hashes(value: &str) -> (u64, u64) {
+        let mut h1 = DefaultHasher::new();
+        value.hash(&mut h1);
+        let h1 = h1.finish();
+
+        let mut h2 = DefaultHasher::new();
+        value.hash(&mut h2);
+        0x9e3779b9_7f4a7c15u64.hash(&mut h2);
+        let h2 = h2.finish();
+
+        (h1, h2)
+    }
+
+    fn bit_indices(&self, value: &str) -> impl Iterator<Item = usize> + '_ {
+        let (h1, h2) = Self::hashes(value);
+        let len = self.bits.len() as u64;
+        (0..self.num_hashes as u64).map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % len) as usize)
+    }
+
+    /// Add `value` to the filter.
+    pub fn insert(&mut self, value: &str) {
+        for index in self.bit_indices(value) {
+            self.bits[index] = true;
+        }
+    }
+
+    /// Test whether `value` may have been inserted. `false` is certain; `true` may be a false
+    /// positive.
+    pub fn contains(&self, value: &str) -> bool {
+        self.bit_indices(value).all(|index| self.bits[index])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inserted_items_are_found() {
+        let mut filter = BloomFilter::new(100, 0.01);
+        for i in 0..100 {
+            filter.insert(&format!("tag_{i}"));
+        }
+        for i in 0..100 {
+            assert!(filter.contains(&format!("tag_{i}")));
+        }
+    }
+
+    #[test]
+    fn test_false_positive_rate_is_roughly_bounded() {
+        let mut filter = BloomFilter::new(1_000, 0.01);
+        for i in 0..1_000 {
+            filter.insert(&format!("tag_{i}"));
+        }
+        let false_positives = (1_000..11_000)
+            .filter(|i| filter.contains(&format!("tag_{i}")))
+            .count();
+        let rate = false_positives as f64 / 10_000.0;
+        assert!(rate < 0.05, "false positive rate {rate} too high");
+    }
+}
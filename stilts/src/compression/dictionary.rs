@@ -6,11 +6,40 @@ use anyhow::{Context, Result};
 use bitvec::prelude::*;
 use std::collections::HashMap;
 
+/// Read a little-endian `u32` length/count field at `*pos`, advancing it past the field.
+fn read_u32_field(bits: &BitSlice<u8, Lsb0>, pos: &mut usize) -> Result<u32> {
+    if *pos + 32 > bits.len() {
+        anyhow::bail!("Insufficient data for u32 field");
+    }
+    let value = bits[*pos..*pos + 32].load::<u32>();
+    *pos += 32;
+    Ok(value)
+}
+
+/// Read a `len`-byte UTF-8 string at `*pos`, advancing it past the field.
+fn read_string_field(bits: &BitSlice<u8, Lsb0>, pos: &mut usize, len: usize) -> Result<String> {
+    if *pos + len * 8 > bits.len() {
+        anyhow::bail!("Insufficient data for string field");
+    }
+    let bytes: Vec<u8> = (0..len)
+        .map(|i| bits[*pos + i * 8..*pos + (i + 1) * 8].load::<u8>())
+        .collect();
+    *pos += len * 8;
+    String::from_utf8(bytes).context("String field was not valid UTF-8")
+}
+
 /// Dictionary-based compressor with variable-length codes
 pub struct DictionaryCompressor {
     dictionary: HashMap<String, u32>,
     reverse_dictionary: HashMap<u32, String>,
     next_code: u32,
+    /// `(min_support, max_abstractions)`, set via `with_pattern_mining`; consulted by
+    /// `build_from_tag_sets` to decide whether (and how much) to mine.
+    pattern_mining: Option<(usize, usize)>,
+    /// Learned multi-tag abstractions, indexed by position: abstraction code `i` expands to
+    /// `abstractions[i]`. Empty unless `build_from_tag_sets` was trained with pattern mining
+    /// enabled, so a plain dictionary's blobs carry only two zero count fields for it.
+    abstractions: Vec<Vec<String>>,
 }
 
 impl DictionaryCompressor {
@@ -20,6 +49,21 @@ impl DictionaryCompressor {
             dictionary: HashMap::new(),
             reverse_dictionary: HashMap::new(),
             next_code: 0,
+            pattern_mining: None,
+            abstractions: Vec::new(),
+        }
+    }
+
+    /// A dictionary compressor that, when trained via `build_from_tag_sets`, additionally
+    /// mines frequent co-occurring tag groups (pairs and triples that appear together at
+    /// least `min_support` times) and promotes up to `max_abstractions` of the
+    /// highest-`support * (members - 1)`-gain groups into single abstraction codes, so a
+    /// group like `{anthro, biped, canine}` that always shows up together compresses to one
+    /// code instead of three.
+    pub fn with_pattern_mining(min_support: usize, max_abstractions: usize) -> Self {
+        Self {
+            pattern_mining: Some((min_support, max_abstractions)),
+            ..Self::new()
         }
     }
 
@@ -51,6 +95,130 @@ impl DictionaryCompressor {
         Ok(())
     }
 
+    /// Train over a corpus of tag *sets* rather than one flat tag list: if pattern mining
+    /// was configured via `with_pattern_mining`, learn abstractions from the sets'
+    /// co-occurrence structure first, then build the base per-tag dictionary exactly as
+    /// `build_from_corpus` would over every tag across every set.
+    pub fn build_from_tag_sets(&mut self, tag_sets: &[Vec<String>]) -> Result<()> {
+        if let Some((min_support, max_abstractions)) = self.pattern_mining {
+            self.abstractions = Self::mine_abstractions(tag_sets, min_support, max_abstractions);
+        }
+
+        let flattened: Vec<String> = tag_sets.iter().flat_map(|set| set.iter().cloned()).collect();
+        self.build_from_corpus(&flattened)
+    }
+
+    /// Greedily mine frequent multi-tag abstractions from `tag_sets`, in an egraph/library-
+    /// learning style loop: start from each set's individual tags as singleton groups, find
+    /// the pair of co-occurring groups across all sets with the highest
+    /// `support * (members - 1)` gain, promote it into a new abstraction, fold that pair into
+    /// one group everywhere it occurs, and repeat — so an already-promoted pair can combine
+    /// with a third tag into a triple on a later round. Stops at `max_abstractions` or once
+    /// no remaining pair clears `min_support`.
+    fn mine_abstractions(
+        tag_sets: &[Vec<String>],
+        min_support: usize,
+        max_abstractions: usize,
+    ) -> Vec<Vec<String>> {
+        let mut doc_groups: Vec<Vec<Vec<String>>> = tag_sets
+            .iter()
+            .map(|set| {
+                let mut unique: Vec<String> = set.clone();
+                unique.sort();
+                unique.dedup();
+                unique.into_iter().map(|tag| vec![tag]).collect()
+            })
+            .collect();
+
+        let mut abstractions: Vec<Vec<String>> = Vec::new();
+
+        while abstractions.len() < max_abstractions {
+            let mut pair_support: HashMap<(Vec<String>, Vec<String>), usize> = HashMap::new();
+            for groups in &doc_groups {
+                for i in 0..groups.len() {
+                    for j in (i + 1)..groups.len() {
+                        let pair = if groups[i] <= groups[j] {
+                            (groups[i].clone(), groups[j].clone())
+                        } else {
+                            (groups[j].clone(), groups[i].clone())
+                        };
+                        *pair_support.entry(pair).or_insert(0) += 1;
+                    }
+                }
+            }
+
+            let best = pair_support
+                .into_iter()
+                .filter(|(_, support)| *support >= min_support)
+                .map(|((a, b), support)| {
+                    let mut merged = a;
+                    merged.extend(b);
+                    merged.sort();
+                    merged.dedup();
+                    let gain = support * merged.len().saturating_sub(1);
+                    (merged, gain)
+                })
+                .max_by_key(|(_, gain)| *gain);
+
+            let Some((merged, gain)) = best else { break };
+            if gain == 0 {
+                break;
+            }
+
+            for groups in &mut doc_groups {
+                let pair_indices = (0..groups.len()).find_map(|i| {
+                    ((i + 1)..groups.len()).find_map(|j| {
+                        let mut union = groups[i].clone();
+                        union.extend(groups[j].clone());
+                        union.sort();
+                        union.dedup();
+                        (union == merged).then_some((i, j))
+                    })
+                });
+                if let Some((i, j)) = pair_indices {
+                    let mut next_groups: Vec<Vec<String>> = groups
+                        .iter()
+                        .enumerate()
+                        .filter(|(k, _)| *k != i && *k != j)
+                        .map(|(_, g)| g.clone())
+                        .collect();
+                    next_groups.push(merged.clone());
+                    *groups = next_groups;
+                }
+            }
+
+            abstractions.push(merged);
+        }
+
+        abstractions
+    }
+
+    /// Greedily replace any of `tags` covered by a learned abstraction's full member set
+    /// with that abstraction's code, trying the largest abstractions first so a big,
+    /// high-gain match isn't starved by a smaller one consuming a shared member first.
+    /// Returns the abstraction codes used and whatever tags were left uncovered.
+    fn apply_abstractions(&self, tags: &[String]) -> (Vec<u32>, Vec<String>) {
+        let mut remaining: Vec<String> = tags.to_vec();
+        let mut used = Vec::new();
+
+        let mut order: Vec<usize> = (0..self.abstractions.len()).collect();
+        order.sort_by_key(|&i| std::cmp::Reverse(self.abstractions[i].len()));
+
+        for idx in order {
+            let members = &self.abstractions[idx];
+            if members.iter().all(|member| remaining.contains(member)) {
+                for member in members {
+                    if let Some(pos) = remaining.iter().position(|tag| tag == member) {
+                        remaining.remove(pos);
+                    }
+                }
+                used.push(idx as u32);
+            }
+        }
+
+        (used, remaining)
+    }
+
     fn code_to_bits(&self, code: u32) -> BitVec<u8, Lsb0> {
         // Use variable-length encoding:
         // - Codes 0-127: 1 byte
@@ -120,10 +288,48 @@ impl DictionaryCompressor {
             result.extend_from_bitslice(code.to_le_bytes().view_bits::<Lsb0>());
         }
 
-        // Encode number of tags
-        result.extend_from_bitslice((tags.len() as u32).to_le_bytes().view_bits::<Lsb0>());
+        // Encode the learned abstraction table (empty unless trained via
+        // `build_from_tag_sets` with pattern mining enabled, so a plain dictionary's wire
+        // format gains only this count and the abstraction-code count below, both zero).
+        result.extend_from_bitslice((self.abstractions.len() as u32).to_le_bytes().view_bits::<Lsb0>());
+        for members in &self.abstractions {
+            result.extend_from_bitslice((members.len() as u32).to_le_bytes().view_bits::<Lsb0>());
+            for tag in members {
+                let tag_bytes = tag.as_bytes();
+                result.extend_from_bitslice((tag_bytes.len() as u32).to_le_bytes().view_bits::<Lsb0>());
+                result.extend_from_bitslice(tag_bytes.view_bits::<Lsb0>());
+            }
+        }
+
+        let (abstraction_codes, remaining) = self.apply_abstractions(tags);
+
+        // Encode the abstraction codes used by this document as fixed-width u32s, since
+        // they index into the abstraction table above rather than the dictionary's
+        // variable-length code space.
+        result.extend_from_bitslice((abstraction_codes.len() as u32).to_le_bytes().view_bits::<Lsb0>());
+        for code in &abstraction_codes {
+            result.extend_from_bitslice(code.to_le_bytes().view_bits::<Lsb0>());
+        }
 
-        // Encode tags using codes
+        // Encode the remaining (non-abstracted) tags using the usual dictionary codes
+        result.extend_from_bitslice((remaining.len() as u32).to_le_bytes().view_bits::<Lsb0>());
+        for tag in &remaining {
+            let code = self
+                .dictionary
+                .get(tag)
+                .with_context(|| format!("Tag not in dictionary: {}", tag))?;
+            result.extend_from_bitslice(&self.code_to_bits(*code));
+        }
+
+        Ok(result)
+    }
+
+    /// Encode only the code stream, without the dictionary header `encode_tags` embeds.
+    /// The dictionary must already be trained via `build_from_corpus` or `load_dictionary`.
+    fn encode_payload(&self, tags: &[String]) -> Result<BitVec<u8, Lsb0>> {
+        let mut result = BitVec::new();
+
+        result.extend_from_bitslice((tags.len() as u32).to_le_bytes().view_bits::<Lsb0>());
         for tag in tags {
             let code = self
                 .dictionary
@@ -135,59 +341,68 @@ impl DictionaryCompressor {
         Ok(result)
     }
 
+    /// Inverse of `encode_payload`, using `self`'s already-loaded dictionary.
+    fn decode_payload(&self, bits: &BitSlice<u8, Lsb0>) -> Result<Vec<String>> {
+        if bits.len() < 32 {
+            anyhow::bail!("Insufficient data for tag count");
+        }
+        let count = bits[0..32].load::<u32>() as usize;
+        let mut remaining_bits = &bits[32..];
+
+        let mut result = Vec::with_capacity(count);
+        for _ in 0..count {
+            let code = self.bits_to_code(&mut remaining_bits)?;
+            let tag = self
+                .reverse_dictionary
+                .get(&code)
+                .with_context(|| format!("Code not in dictionary: {}", code))?;
+            result.push(tag.clone());
+        }
+
+        Ok(result)
+    }
+
     fn decode_tags(&self, bits: &BitSlice<u8, Lsb0>) -> Result<Vec<String>> {
         let mut pos = 0;
-        let mut dictionary = HashMap::new();
         let mut reverse_dict = HashMap::new();
 
-        // Decode dictionary size
-        if pos + 32 > bits.len() {
-            anyhow::bail!("Insufficient data for dictionary size");
-        }
-        let dict_size = bits[pos..pos + 32].load::<u32>() as usize;
-        pos += 32;
-
         // Decode dictionary
+        let dict_size = read_u32_field(bits, &mut pos)? as usize;
         for _ in 0..dict_size {
-            // Decode tag length
-            if pos + 32 > bits.len() {
-                anyhow::bail!("Insufficient data for tag length");
-            }
-            let tag_len = bits[pos..pos + 32].load::<u32>() as usize;
-            pos += 32;
-
-            // Decode tag
-            if pos + tag_len * 8 > bits.len() {
-                anyhow::bail!("Insufficient data for tag");
-            }
-            let tag_bytes: Vec<u8> = (0..tag_len)
-                .map(|i| bits[pos + i * 8..pos + (i + 1) * 8].load::<u8>())
-                .collect();
-            let tag = String::from_utf8(tag_bytes)?;
-            pos += tag_len * 8;
-
-            // Decode code
-            if pos + 32 > bits.len() {
-                anyhow::bail!("Insufficient data for code");
-            }
-            let code = bits[pos..pos + 32].load::<u32>();
-            pos += 32;
-
-            dictionary.insert(tag.clone(), code);
+            let tag_len = read_u32_field(bits, &mut pos)? as usize;
+            let tag = read_string_field(bits, &mut pos, tag_len)?;
+            let code = read_u32_field(bits, &mut pos)?;
             reverse_dict.insert(code, tag);
         }
 
-        // Decode number of tags
-        if pos + 32 > bits.len() {
-            anyhow::bail!("Insufficient data for tag count");
+        // Decode the abstraction table
+        let num_abstractions = read_u32_field(bits, &mut pos)? as usize;
+        let mut abstractions = Vec::with_capacity(num_abstractions);
+        for _ in 0..num_abstractions {
+            let member_count = read_u32_field(bits, &mut pos)? as usize;
+            let mut members = Vec::with_capacity(member_count);
+            for _ in 0..member_count {
+                let tag_len = read_u32_field(bits, &mut pos)? as usize;
+                members.push(read_string_field(bits, &mut pos, tag_len)?);
+            }
+            abstractions.push(members);
         }
-        let count = bits[pos..pos + 32].load::<u32>() as usize;
-        pos += 32;
 
-        // Decode tags
+        // Decode the abstraction codes used by this document, expanding each back to its
+        // member tags
         let mut result = Vec::new();
-        let mut remaining_bits = &bits[pos..];
+        let num_abstraction_codes = read_u32_field(bits, &mut pos)? as usize;
+        for _ in 0..num_abstraction_codes {
+            let index = read_u32_field(bits, &mut pos)? as usize;
+            let members = abstractions
+                .get(index)
+                .with_context(|| format!("Unknown abstraction code: {}", index))?;
+            result.extend(members.iter().cloned());
+        }
 
+        // Decode the remaining (non-abstracted) tags via the usual dictionary codes
+        let count = read_u32_field(bits, &mut pos)? as usize;
+        let mut remaining_bits = &bits[pos..];
         for _ in 0..count {
             let code = self.bits_to_code(&mut remaining_bits)?;
             let tag = reverse_dict
@@ -200,12 +415,121 @@ impl DictionaryCompressor {
     }
 }
 
+impl DictionaryCompressor {
+    /// Assign codes to any tags not already in the dictionary, without touching existing
+    /// entries. Returns the newly added `(tag, code)` pairs in the order they were assigned,
+    /// so a caller (e.g. a streaming compressor) can ship just the delta to a peer that
+    /// already holds the prior state.
+    pub(crate) fn learn(&mut self, tags: &[String]) -> Vec<(String, u32)> {
+        let mut new_entries = Vec::new();
+        for tag in tags {
+            if !self.dictionary.contains_key(tag) {
+                let code = self.next_code;
+                self.next_code += 1;
+                self.dictionary.insert(tag.clone(), code);
+                self.reverse_dictionary.insert(code, tag.clone());
+                new_entries.push((tag.clone(), code));
+            }
+        }
+        new_entries
+    }
+
+    /// Insert a single dictionary entry with an explicit code, as learned from a peer's
+    /// streaming continuation header.
+    pub(crate) fn learn_entry(&mut self, tag: String, code: u32) {
+        self.next_code = self.next_code.max(code + 1);
+        self.dictionary.insert(tag.clone(), code);
+        self.reverse_dictionary.insert(code, tag);
+    }
+
+    /// "Train once, compress many": emit only the code stream, assuming the dictionary
+    /// (built via `build_from_corpus` or `load_dictionary`) is already shared out of band.
+    /// Falls back to the self-describing `compress` when no dictionary has been trained.
+    pub fn compress_payload(&self, tags: &[String]) -> Result<Vec<u8>> {
+        if self.dictionary.is_empty() {
+            return self.compress(tags);
+        }
+        Ok(self.encode_payload(tags)?.into_vec())
+    }
+
+    /// Inverse of `compress_payload`, decoding against `self`'s already-loaded dictionary.
+    pub fn decompress_payload(&self, data: &[u8]) -> Result<Vec<String>> {
+        if self.dictionary.is_empty() {
+            anyhow::bail!("Dictionary not trained; call build_from_corpus or load_dictionary first");
+        }
+        self.decode_payload(data.view_bits::<Lsb0>())
+    }
+
+    /// Serialize the trained dictionary so it can be persisted to disk and shared
+    /// across many `compress_payload` calls via `load_dictionary`.
+    pub fn serialize_dictionary(&self) -> Result<Vec<u8>> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&(self.dictionary.len() as u32).to_le_bytes());
+        for (tag, code) in &self.dictionary {
+            let tag_bytes = tag.as_bytes();
+            out.extend_from_slice(&(tag_bytes.len() as u32).to_le_bytes());
+            out.extend_from_slice(tag_bytes);
+            out.extend_from_slice(&code.to_le_bytes());
+        }
+        Ok(out)
+    }
+
+    /// Rebuild a `DictionaryCompressor` from bytes produced by `serialize_dictionary`.
+    pub fn load_dictionary(data: &[u8]) -> Result<Self> {
+        let mut pos = 0;
+        let count_bytes: [u8; 4] = data
+            .get(pos..pos + 4)
+            .context("Truncated dictionary entry count")?
+            .try_into()?;
+        let count = u32::from_le_bytes(count_bytes) as usize;
+        pos += 4;
+
+        let mut dictionary = HashMap::with_capacity(count);
+        let mut reverse_dictionary = HashMap::with_capacity(count);
+        let mut next_code = 0;
+
+        for _ in 0..count {
+            let len_bytes: [u8; 4] = data
+                .get(pos..pos + 4)
+                .context("Truncated dictionary tag length")?
+                .try_into()?;
+            let len = u32::from_le_bytes(len_bytes) as usize;
+            pos += 4;
+
+            let tag_bytes = data.get(pos..pos + len).context("Truncated dictionary tag")?;
+            let tag = String::from_utf8(tag_bytes.to_vec())?;
+            pos += len;
+
+            let code_bytes: [u8; 4] = data
+                .get(pos..pos + 4)
+                .context("Truncated dictionary code")?
+                .try_into()?;
+            let code = u32::from_le_bytes(code_bytes);
+            pos += 4;
+
+            next_code = next_code.max(code + 1);
+            dictionary.insert(tag.clone(), code);
+            reverse_dictionary.insert(code, tag);
+        }
+
+        Ok(Self {
+            dictionary,
+            reverse_dictionary,
+            next_code,
+            pattern_mining: None,
+            abstractions: Vec::new(),
+        })
+    }
+}
+
 impl Clone for DictionaryCompressor {
     fn clone(&self) -> Self {
         Self {
             dictionary: self.dictionary.clone(),
             reverse_dictionary: self.reverse_dictionary.clone(),
             next_code: self.next_code,
+            pattern_mining: self.pattern_mining,
+            abstractions: self.abstractions.clone(),
         }
     }
 }
@@ -231,18 +555,30 @@ impl Compressor for DictionaryCompressor {
         // Encode tags
         let bits = compressor.encode_tags(tags)?;
 
-        // Convert to bytes
-        Ok(bits.into_vec())
+        // Convert to bytes, prefixed with the method ID so the blob is self-describing
+        let mut out = vec![crate::compression::CompressionMethod::Dictionary.id()];
+        out.extend(bits.into_vec());
+        Ok(out)
     }
 
     fn decompress(&self, data: &[u8]) -> Result<Vec<String>> {
-        let bits = data.view_bits::<Lsb0>();
+        let payload = data.split_first().context("Empty dictionary blob")?.1;
+        let bits = payload.view_bits::<Lsb0>();
         self.decode_tags(bits)
     }
 
     fn algorithm_name(&self) -> &'static str {
         "dictionary"
     }
+
+    fn save_model(&self) -> Result<Vec<u8>> {
+        self.serialize_dictionary()
+    }
+
+    fn load_model(&mut self, data: &[u8]) -> Result<()> {
+        *self = Self::load_dictionary(data)?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -261,4 +597,102 @@ mod tests {
 
         assert_eq!(tags, decompressed);
     }
+
+    #[test]
+    fn test_compress_payload_is_smaller_than_self_describing() {
+        let tags = vec!["tag1".to_string(), "tag2".to_string(), "tag1".to_string()];
+
+        let mut compressor = DictionaryCompressor::new();
+        compressor.build_from_corpus(&tags).unwrap();
+
+        let self_describing = compressor.compress(&tags).unwrap();
+        let payload_only = compressor.compress_payload(&tags).unwrap();
+
+        assert!(payload_only.len() < self_describing.len());
+        assert_eq!(tags, compressor.decompress_payload(&payload_only).unwrap());
+    }
+
+    #[test]
+    fn test_serialize_and_load_dictionary() {
+        let tags = vec!["tag1".to_string(), "tag2".to_string(), "tag3".to_string()];
+
+        let mut trained = DictionaryCompressor::new();
+        trained.build_from_corpus(&tags).unwrap();
+        let serialized = trained.serialize_dictionary().unwrap();
+
+        let loaded = DictionaryCompressor::load_dictionary(&serialized).unwrap();
+        let payload = trained.compress_payload(&tags).unwrap();
+
+        assert_eq!(tags, loaded.decompress_payload(&payload).unwrap());
+    }
+
+    #[test]
+    fn test_compressor_trait_save_and_load_model_round_trips() {
+        let tags = vec!["tag1".to_string(), "tag2".to_string(), "tag1".to_string()];
+
+        let mut trained = DictionaryCompressor::new();
+        trained.build_from_corpus(&tags).unwrap();
+        let model = trained.save_model().unwrap();
+
+        let mut restored = DictionaryCompressor::new();
+        restored.load_model(&model).unwrap();
+
+        let compressed = restored.compress(&tags).unwrap();
+        assert_eq!(restored.decompress(&compressed).unwrap(), tags);
+    }
+
+    #[test]
+    fn test_pattern_mining_learns_and_round_trips_abstraction() {
+        let tag_sets = vec![
+            vec!["anthro".to_string(), "biped".to_string(), "canine".to_string()],
+            vec!["anthro".to_string(), "biped".to_string(), "canine".to_string()],
+            vec!["anthro".to_string(), "biped".to_string(), "canine".to_string()],
+            vec!["anthro".to_string(), "biped".to_string(), "feline".to_string()],
+        ];
+
+        let mut compressor = DictionaryCompressor::with_pattern_mining(2, 8);
+        compressor.build_from_tag_sets(&tag_sets).unwrap();
+
+        assert!(
+            !compressor.abstractions.is_empty(),
+            "expected at least one abstraction to be mined from a repeated tag group"
+        );
+
+        for set in &tag_sets {
+            let compressed = compressor.compress(set).unwrap();
+            let mut decompressed = compressor.decompress(&compressed).unwrap();
+            let mut expected = set.clone();
+            decompressed.sort();
+            expected.sort();
+            assert_eq!(expected, decompressed);
+        }
+    }
+
+    #[test]
+    fn test_pattern_mining_improves_ratio_over_flat_dictionary() {
+        let tag_sets: Vec<Vec<String>> = (0..50)
+            .map(|_| {
+                vec![
+                    "anthro".to_string(),
+                    "biped".to_string(),
+                    "canine".to_string(),
+                    "domestic_dog".to_string(),
+                ]
+            })
+            .collect();
+
+        let mut flat = DictionaryCompressor::new();
+        let flattened: Vec<String> = tag_sets.iter().flat_map(|s| s.iter().cloned()).collect();
+        flat.build_from_corpus(&flattened).unwrap();
+        let flat_total: usize = tag_sets.iter().map(|s| flat.compress(s).unwrap().len()).sum();
+
+        let mut mined = DictionaryCompressor::with_pattern_mining(2, 8);
+        mined.build_from_tag_sets(&tag_sets).unwrap();
+        let mined_total: usize = tag_sets.iter().map(|s| mined.compress(s).unwrap().len()).sum();
+
+        assert!(
+            mined_total < flat_total,
+            "pattern-mined total {mined_total} should beat flat dictionary total {flat_total}"
+        );
+    }
 }
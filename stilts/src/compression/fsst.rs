@@ -0,0 +1,383 @@
+//! Fast Static Symbol Table (FSST) compression
+//!
+//! Unlike `DictionaryCompressor`, which assigns one code per whole tag, FSST builds a
+//! table of short byte-string symbols (length 1-8) and greedily matches the longest
+//! symbol at each position, so tags sharing common substrings compress well even when
+//! the tags themselves never repeat verbatim.
+
+use crate::compression::Compressor;
+use anyhow::{Context, Result, bail};
+use std::collections::HashMap;
+
+/// Code value reserved to mean "the next byte is a literal, not a symbol".
+const ESCAPE: u8 = 255;
+/// At most 255 symbols fit in a one-byte code, since 255 is reserved for escape.
+const MAX_SYMBOLS: usize = 255;
+/// Symbols longer than this stop paying off relative to the one-byte code they cost.
+const MAX_SYMBOL_LEN: usize = 8;
+/// Number of train/re-score iterations when growing the symbol table from single bytes.
+const TRAINING_ROUNDS: usize = 5;
+
+/// FSST symbol-table compressor
+#[derive(Clone)]
+pub struct FsstCompressor {
+    /// `symbols[code]` is the byte string that code expands to.
+    symbols: Vec<Vec<u8>>,
+    /// First-two-bytes index into `symbols`, candidates sorted longest-first so the
+    /// first match found during lookup is already the longest match.
+    index: HashMap<(u8, u8), Vec<u8>>,
+    /// Code for each single byte that has its own one-byte symbol. A byte with none (unseen
+    /// in training, or dropped from the table by a later training round) is encoded as
+    /// `ESCAPE` plus the literal byte.
+    single_byte: HashMap<u8, u8>,
+}
+
+impl FsstCompressor {
+    /// Create an untrained compressor; call `train_bulk` before compressing.
+    pub fn new() -> Self {
+        Self {
+            symbols: Vec::new(),
+            index: HashMap::new(),
+            single_byte: HashMap::new(),
+        }
+    }
+
+    fn rebuild_lookup(&mut self) {
+        self.index.clear();
+        self.single_byte.clear();
+
+        for (code, symbol) in self.symbols.iter().enumerate() {
+            let code = code as u8;
+            if symbol.len() == 1 {
+                self.single_byte.insert(symbol[0], code);
+            }
+            if symbol.len() >= 2 {
+                self.index
+                    .entry((symbol[0], symbol[1]))
+                    .or_default()
+                    .push(code);
+            }
+        }
+
+        for candidates in self.index.values_mut() {
+            candidates.sort_by_key(|&code| std::cmp::Reverse(self.symbols[code as usize].len()));
+        }
+    }
+
+    /// Find the longest symbol matching the bytes starting at `pos`, if any.
+    fn longest_match(&self, data: &[u8], pos: usize) -> Option<(u8, usize)> {
+        if pos + 1 < data.len() {
+            if let Some(candidates) = self.index.get(&(data[pos], data[pos + 1])) {
+                for &code in candidates {
+                    let symbol = &self.symbols[code as usize];
+                    if pos + symbol.len() <= data.len() && &data[pos..pos + symbol.len()] == symbol.as_slice()
+                    {
+                        return Some((code, symbol.len()));
+                    }
+                }
+            }
+        }
+
+        self.single_byte.get(&data[pos]).map(|&code| (code, 1))
+    }
+
+    /// Emit one code per matched symbol, or `ESCAPE` followed by a literal byte.
+    fn encode_bytes(&self, data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut pos = 0;
+        while pos < data.len() {
+            match self.longest_match(data, pos) {
+                Some((code, len)) => {
+                    out.push(code);
+                    pos += len;
+                }
+                None => {
+                    out.push(ESCAPE);
+                    out.push(data[pos]);
+                    pos += 1;
+                }
+            }
+        }
+        out
+    }
+
+    fn decode_bytes(symbols: &[Vec<u8>], codes: &[u8]) -> Result<Vec<u8>> {
+        let mut out = Vec::new();
+        let mut i = 0;
+        while i < codes.len() {
+            if codes[i] == ESCAPE {
+                i += 1;
+                let literal = *codes.get(i).context("Truncated escape sequence")?;
+                out.push(literal);
+                i += 1;
+            } else {
+                let symbol = symbols
+                    .get(codes[i] as usize)
+                    .with_context(|| format!("Unknown symbol code: {}", codes[i]))?;
+                out.extend_from_slice(symbol);
+                i += 1;
+            }
+        }
+        Ok(out)
+    }
+
+    /// Train the symbol table over the whole corpus at once (tags are concatenated
+    /// for training purposes only; compression still operates tag-by-tag).
+    pub fn train_bulk(&mut self, tags: &[String]) -> Result<()> {
+        let sample: Vec<u8> = tags.iter().flat_map(|tag| tag.bytes()).collect();
+
+        if sample.is_empty() {
+            self.symbols = Vec::new();
+            self.rebuild_lookup();
+            return Ok(());
+        }
+
+        let mut seen = [false; 256];
+        for &byte in &sample {
+            seen[byte as usize] = true;
+        }
+        self.symbols = (0u16..256)
+            .filter(|&b| seen[b as usize])
+            .take(MAX_SYMBOLS)
+            .map(|b| vec![b as u8])
+            .collect();
+        self.rebuild_lookup();
+
+        for _ in 0..TRAINING_ROUNDS {
+            let codes = self.encode_bytes(&sample);
+
+            let mut symbol_freq: HashMap<u8, usize> = HashMap::new();
+            let mut pair_freq: HashMap<(u8, u8), usize> = HashMap::new();
+            let mut prev: Option<u8> = None;
+            let mut i = 0;
+            while i < codes.len() {
+                if codes[i] == ESCAPE {
+                    i += 2;
+                    prev = None;
+                    continue;
+                }
+                let code = codes[i];
+                *symbol_freq.entry(code).or_insert(0) += 1;
+                if let Some(p) = prev {
+                    *pair_freq.entry((p, code)).or_insert(0) += 1;
+                }
+                prev = Some(code);
+                i += 1;
+            }
+
+            let mut scores: HashMap<Vec<u8>, usize> = HashMap::new();
+            for (&code, &freq) in &symbol_freq {
+                let symbol = self.symbols[code as usize].clone();
+                let gain = freq * symbol.len();
+                *scores.entry(symbol).or_insert(0) += gain;
+            }
+            for (&(a, b), &freq) in &pair_freq {
+                let mut candidate = self.symbols[a as usize].clone();
+                candidate.extend_from_slice(&self.symbols[b as usize]);
+                candidate.truncate(MAX_SYMBOL_LEN);
+                let gain = freq * candidate.len();
+                *scores.entry(candidate).or_insert(0) += gain;
+            }
+
+            let mut ranked: Vec<(Vec<u8>, usize)> = scores.into_iter().collect();
+            ranked.sort_by(|a, b| b.1.cmp(&a.1));
+            ranked.truncate(MAX_SYMBOLS);
+
+            self.symbols = ranked.into_iter().map(|(symbol, _)| symbol).collect();
+            self.rebuild_lookup();
+        }
+
+        Ok(())
+    }
+
+    /// Alias for `train_bulk`, so callers that configure a compressor generically (e.g. by
+    /// algorithm name) have one `train` entry point to call regardless of backend.
+    pub fn train(&mut self, tags: &[String]) -> Result<()> {
+        self.train_bulk(tags)
+    }
+
+    /// Serialize just the trained symbol table (the same header `encode_tags` embeds in
+    /// every blob), so it can be persisted and reloaded via `load_model` without retraining
+    /// on the original corpus.
+    fn serialize_symbols(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.push(self.symbols.len() as u8);
+        for symbol in &self.symbols {
+            out.push(symbol.len() as u8);
+            out.extend_from_slice(symbol);
+        }
+        out
+    }
+
+    /// Inverse of `serialize_symbols`, advancing `pos` past the bytes it consumed so callers
+    /// parsing a full blob (where the symbol table is just the leading section) can continue
+    /// reading from where it left off.
+    fn parse_symbols(data: &[u8], pos: &mut usize) -> Result<Vec<Vec<u8>>> {
+        let num_symbols = *data.get(*pos).context("Empty FSST symbol table")? as usize;
+        *pos += 1;
+
+        let mut symbols = Vec::with_capacity(num_symbols);
+        for _ in 0..num_symbols {
+            let len = *data.get(*pos).context("Truncated symbol table")? as usize;
+            *pos += 1;
+            let bytes = data
+                .get(*pos..*pos + len)
+                .context("Truncated symbol bytes")?
+                .to_vec();
+            *pos += len;
+            symbols.push(bytes);
+        }
+        Ok(symbols)
+    }
+
+    fn encode_tags(&self, tags: &[String]) -> Vec<u8> {
+        let mut out = self.serialize_symbols();
+
+        out.extend_from_slice(&(tags.len() as u32).to_le_bytes());
+        for tag in tags {
+            let codes = self.encode_bytes(tag.as_bytes());
+            out.extend_from_slice(&(codes.len() as u32).to_le_bytes());
+            out.extend_from_slice(&codes);
+        }
+        out
+    }
+
+    fn decode_tags(data: &[u8]) -> Result<Vec<String>> {
+        let mut pos = 0;
+        let symbols = Self::parse_symbols(data, &mut pos)?;
+
+        let count_bytes: [u8; 4] = data
+            .get(pos..pos + 4)
+            .context("Truncated tag count")?
+            .try_into()?;
+        let count = u32::from_le_bytes(count_bytes) as usize;
+        pos += 4;
+
+        let mut tags = Vec::with_capacity(count);
+        for _ in 0..count {
+            let len_bytes: [u8; 4] = data
+                .get(pos..pos + 4)
+                .context("Truncated tag code length")?
+                .try_into()?;
+            let len = u32::from_le_bytes(len_bytes) as usize;
+            pos += 4;
+            let codes = data.get(pos..pos + len).context("Truncated tag codes")?;
+            pos += len;
+
+            let bytes = Self::decode_bytes(&symbols, codes)?;
+            tags.push(String::from_utf8(bytes).context("FSST output was not valid UTF-8")?);
+        }
+
+        Ok(tags)
+    }
+}
+
+impl Default for FsstCompressor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Compressor for FsstCompressor {
+    fn compress(&self, tags: &[String]) -> Result<Vec<u8>> {
+        let encoded = if self.symbols.is_empty() {
+            let mut trained = self.clone();
+            trained.train_bulk(tags)?;
+            trained.encode_tags(tags)
+        } else {
+            self.encode_tags(tags)
+        };
+
+        // Prefix with the method ID so the blob is self-describing
+        let mut out = vec![crate::compression::CompressionMethod::Fsst.id()];
+        out.extend(encoded);
+        Ok(out)
+    }
+
+    fn decompress(&self, data: &[u8]) -> Result<Vec<String>> {
+        if data.is_empty() {
+            bail!("Cannot decompress empty FSST blob");
+        }
+        let payload = data.split_first().context("Empty FSST blob")?.1;
+        Self::decode_tags(payload)
+    }
+
+    fn algorithm_name(&self) -> &'static str {
+        "fsst"
+    }
+
+    fn save_model(&self) -> Result<Vec<u8>> {
+        Ok(self.serialize_symbols())
+    }
+
+    fn load_model(&mut self, data: &[u8]) -> Result<()> {
+        let mut pos = 0;
+        self.symbols = Self::parse_symbols(data, &mut pos)?;
+        self.rebuild_lookup();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fsst_round_trip() {
+        let tags = vec![
+            "scott_pilgrim".to_string(),
+            "scott_summers".to_string(),
+            "pilgrim_route".to_string(),
+        ];
+
+        let mut compressor = FsstCompressor::new();
+        compressor.train_bulk(&tags).unwrap();
+
+        let compressed = compressor.compress(&tags).unwrap();
+        let decompressed = compressor.decompress(&compressed).unwrap();
+
+        assert_eq!(tags, decompressed);
+    }
+
+    #[test]
+    fn test_fsst_handles_untrained_bytes() {
+        let mut compressor = FsstCompressor::new();
+        compressor.train_bulk(&["abc".to_string()]).unwrap();
+
+        let tags = vec!["abc".to_string(), "xyz123".to_string()];
+        let compressed = compressor.compress(&tags).unwrap();
+        let decompressed = FsstCompressor::new().decompress(&compressed).unwrap();
+
+        assert_eq!(tags, decompressed);
+    }
+
+    #[test]
+    fn test_fsst_compress_trains_when_untrained() {
+        let tags = vec!["repeat_repeat".to_string(), "repeat_again".to_string()];
+        let compressor = FsstCompressor::new();
+
+        let compressed = compressor.compress(&tags).unwrap();
+        let decompressed = compressor.decompress(&compressed).unwrap();
+
+        assert_eq!(tags, decompressed);
+    }
+
+    #[test]
+    fn test_save_and_load_model_round_trips() {
+        let tags = vec![
+            "scott_pilgrim".to_string(),
+            "scott_summers".to_string(),
+            "pilgrim_route".to_string(),
+        ];
+
+        let mut trained = FsstCompressor::new();
+        trained.train_bulk(&tags).unwrap();
+        let model = trained.save_model().unwrap();
+
+        let mut restored = FsstCompressor::new();
+        restored.load_model(&model).unwrap();
+
+        let compressed = restored.compress(&tags).unwrap();
+        assert_eq!(restored.decompress(&compressed).unwrap(), tags);
+    }
+}
@@ -5,6 +5,7 @@ use std::cmp::Ordering;
 use anyhow::{Result, Context};
 use bitvec::prelude::*;
 use crate::compression::Compressor;
+use crate::compression::hyperloglog::HyperLogLog;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 struct HuffmanNode {
@@ -26,11 +27,122 @@ impl PartialOrd for HuffmanNode {
     }
 }
 
-/// Huffman coding compressor
+/// Assigns canonical Huffman codes (RFC 1951 style) to `entries`, which must already be
+/// sorted by `(length, tag)` so that the code assigned to each symbol is reproducible from
+/// the header alone: codes of the same length are consecutive integers assigned in the
+/// entries' order, and the starting code for each length is derived purely from how many
+/// symbols precede it at shorter lengths.
+fn assign_canonical_codes(entries: &[(String, u8)]) -> HashMap<String, (u32, u8)> {
+    let max_len = entries.iter().map(|(_, len)| *len as usize).max().unwrap_or(0);
+    let mut count_per_length = vec![0u32; max_len + 1];
+    for (_, len) in entries {
+        count_per_length[*len as usize] += 1;
+    }
+
+    let mut next_code = vec![0u32; max_len + 1];
+    let mut code = 0u32;
+    for length in 1..=max_len {
+        code = (code + count_per_length[length - 1]) << 1;
+        next_code[length] = code;
+    }
+
+    let mut codes = HashMap::with_capacity(entries.len());
+    for (tag, len) in entries {
+        let length = *len as usize;
+        codes.insert(tag.clone(), (next_code[length], *len));
+        next_code[length] += 1;
+    }
+    codes
+}
+
+/// A decode-side view of the same canonical assignment `assign_canonical_codes` produces,
+/// but shaped for O(code length) symbol lookup (a first-code/first-index table per length,
+/// the same trick DEFLATE uses) instead of a linear scan over every known code.
+struct CanonicalDecodeTable {
+    /// Symbols in the same `(length, tag)` order the header listed them in.
+    tags: Vec<String>,
+    /// `first_code[len]` is the smallest code value assigned to a symbol of that length.
+    first_code: Vec<u32>,
+    /// `first_index[len]` is where symbols of that length start within `tags`.
+    first_index: Vec<usize>,
+    /// `count[len]` is how many symbols have that length.
+    count: Vec<u32>,
+}
+
+impl CanonicalDecodeTable {
+    fn build(entries: &[(String, u8)]) -> Self {
+        let max_len = entries.iter().map(|(_, len)| *len as usize).max().unwrap_or(0);
+        let mut count = vec![0u32; max_len + 1];
+        for (_, len) in entries {
+            count[*len as usize] += 1;
+        }
+
+        let mut first_code = vec![0u32; max_len + 1];
+        let mut code = 0u32;
+        for length in 1..=max_len {
+            code = (code + count[length - 1]) << 1;
+            first_code[length] = code;
+        }
+
+        let mut first_index = vec![0usize; max_len + 1];
+        let mut running = 0usize;
+        for length in 1..=max_len {
+            first_index[length] = running;
+            running += count[length] as usize;
+        }
+
+        let tags = entries.iter().map(|(tag, _)| tag.clone()).collect();
+        Self { tags, first_code, first_index, count }
+    }
+
+    /// Decode one symbol starting at bit offset `start`, returning the tag and how many
+    /// bits it consumed.
+    fn decode_one(&self, bits: &BitSlice<u8, Lsb0>, start: usize) -> Result<(String, usize)> {
+        let mut code = 0u32;
+        let mut length = 0usize;
+        loop {
+            let pos = start + length;
+            if pos >= bits.len() {
+                anyhow::bail!("Truncated Huffman payload while decoding a symbol");
+            }
+            let bit = bits[pos..pos + 1].load::<u8>();
+            code = (code << 1) | bit as u32;
+            length += 1;
+
+            if length >= self.first_code.len() {
+                anyhow::bail!("No canonical symbol matched after {} bits", length);
+            }
+            let count = self.count[length];
+            if count > 0 && code >= self.first_code[length] && code - self.first_code[length] < count {
+                let index = self.first_index[length] + (code - self.first_code[length]) as usize;
+                return Ok((self.tags[index].clone(), length));
+            }
+        }
+    }
+}
+
+fn read_u32(data: &[u8], pos: &mut usize) -> Result<u32> {
+    let bytes: [u8; 4] = data.get(*pos..*pos + 4).context("Truncated u32 field")?.try_into()?;
+    *pos += 4;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u16(data: &[u8], pos: &mut usize) -> Result<u16> {
+    let bytes: [u8; 2] = data.get(*pos..*pos + 2).context("Truncated u16 field")?.try_into()?;
+    *pos += 2;
+    Ok(u16::from_le_bytes(bytes))
+}
+
+/// Huffman coding compressor.
+///
+/// Codes are canonical: only each symbol's code *length* is load-bearing, so a compressed
+/// blob carries its own `[num_symbols][(length, tag_len, tag_bytes)...][num_tags]` header
+/// and can be decompressed by any `HuffmanCompressor`, trained or not, rather than only the
+/// exact instance that produced it.
 #[derive(Clone)]
 pub struct HuffmanCompressor {
-    codes: HashMap<String, BitVec<u8, Lsb0>>,
-    reverse_codes: HashMap<BitVec<u8, Lsb0>, String>,
+    codes: HashMap<String, (u32, u8)>,
+    distinct_tags_estimate: Option<f64>,
 }
 
 impl HuffmanCompressor {
@@ -38,10 +150,10 @@ impl HuffmanCompressor {
     pub fn new() -> Self {
         Self {
             codes: HashMap::new(),
-            reverse_codes: HashMap::new(),
+            distinct_tags_estimate: None,
         }
     }
-    
+
     /// Build Huffman codes from a corpus of tags
     pub fn build_from_corpus(&mut self, corpus: &[String]) -> Result<()> {
         // Count frequencies
@@ -49,25 +161,53 @@ impl HuffmanCompressor {
         for tag in corpus {
             *frequencies.entry(tag.clone()).or_insert(0) += 1;
         }
-        
-        // Build Huffman tree
+
+        let root = self.build_tree(&frequencies)?;
+        self.codes = Self::codes_from_tree(&root);
+        self.distinct_tags_estimate = None;
+
+        Ok(())
+    }
+
+    /// Build Huffman codes from a tag stream without requiring the whole corpus to be
+    /// materialized into a `Vec<String>` first. Frequencies are still counted exactly per
+    /// distinct tag — Huffman's code table is sized by distinct tags, not total
+    /// occurrences, so every tag still needs an exact count to get a correct code — but the
+    /// input is consumed once as an iterator, so the caller never needs to hold every
+    /// occurrence in memory simultaneously. A `HyperLogLog` cardinality estimator runs
+    /// alongside and is exposed via `distinct_tags_estimate`, useful when a caller wants a
+    /// cheap sizing signal without relying on the exact frequency table's key count.
+    pub fn build_corpus_streaming(&mut self, tags: impl Iterator<Item = String>) -> Result<()> {
+        let mut frequencies: HashMap<String, usize> = HashMap::new();
+        let mut distinct_estimator = HyperLogLog::new();
+
+        for tag in tags {
+            distinct_estimator.add(&tag);
+            *frequencies.entry(tag).or_insert(0) += 1;
+        }
+
         let root = self.build_tree(&frequencies)?;
-        
-        // Generate codes
-        self.codes.clear();
-        self.reverse_codes.clear();
-        self.generate_codes(&root, BitVec::new());
-        
+        self.codes = Self::codes_from_tree(&root);
+        self.distinct_tags_estimate = Some(distinct_estimator.estimate());
+
         Ok(())
     }
-    
+
+    /// The HyperLogLog-estimated number of distinct tags seen by the most recent
+    /// `build_corpus_streaming` call, or `None` if the corpus was built via
+    /// `build_from_corpus` instead (which counts distinct tags exactly via its frequency
+    /// table and has no need for an estimate).
+    pub fn distinct_tags_estimate(&self) -> Option<f64> {
+        self.distinct_tags_estimate
+    }
+
     fn build_tree(&self, frequencies: &HashMap<String, usize>) -> Result<HuffmanNode> {
         if frequencies.is_empty() {
             anyhow::bail!("Cannot build tree from empty frequency table");
         }
-        
+
         let mut heap = BinaryHeap::new();
-        
+
         // Create leaf nodes
         for (tag, freq) in frequencies {
             heap.push(HuffmanNode {
@@ -77,103 +217,145 @@ impl HuffmanCompressor {
                 right: None,
             });
         }
-        
+
         // Build tree
         while heap.len() > 1 {
             let left = heap.pop().unwrap();
             let right = heap.pop().unwrap();
-            
+
             let merged = HuffmanNode {
                 frequency: left.frequency + right.frequency,
                 tag: None,
                 left: Some(Box::new(left)),
                 right: Some(Box::new(right)),
             };
-            
+
             heap.push(merged);
         }
-        
+
         Ok(heap.pop().context("Failed to build Huffman tree")?)
     }
-    
-    fn generate_codes(&mut self, node: &HuffmanNode, mut code: BitVec<u8, Lsb0>) {
+
+    /// Walk the tree to get each symbol's canonical code length, then assign canonical
+    /// codes over the `(length, tag)`-sorted symbol list so two compressors that build the
+    /// same tree always emit the same codes regardless of `HashMap` iteration order.
+    fn codes_from_tree(root: &HuffmanNode) -> HashMap<String, (u32, u8)> {
+        let mut lengths = HashMap::new();
+        Self::generate_lengths(root, 0, &mut lengths);
+
+        let mut entries: Vec<(String, u8)> = lengths.into_iter().collect();
+        entries.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+
+        assign_canonical_codes(&entries)
+    }
+
+    fn generate_lengths(node: &HuffmanNode, depth: u8, lengths: &mut HashMap<String, u8>) {
         if let Some(ref tag) = node.tag {
-            // Leaf node
-            self.codes.insert(tag.clone(), code.clone());
-            self.reverse_codes.insert(code, tag.clone());
+            // A single-symbol corpus has depth 0 (no branch was ever taken), but every
+            // symbol still needs at least one bit to be representable in the payload.
+            lengths.insert(tag.clone(), depth.max(1));
         } else {
-            // Internal node
             if let Some(ref left) = node.left {
-                let mut left_code = code.clone();
-                left_code.push(false);
-                self.generate_codes(left, left_code);
+                Self::generate_lengths(left, depth + 1, lengths);
             }
-            
             if let Some(ref right) = node.right {
-                code.push(true);
-                self.generate_codes(right, code);
+                Self::generate_lengths(right, depth + 1, lengths);
             }
         }
     }
-    
-    fn encode_tags(&self, tags: &[String]) -> Result<BitVec<u8, Lsb0>> {
-        let mut result = BitVec::new();
-        
-        // Encode number of tags (u32)
-        let count = tags.len() as u32;
-        result.extend_from_bitslice(&count.view_bits::<Lsb0>());
-        
-        // Encode each tag
+
+    fn code_to_bits(code: u32, len: u8) -> BitVec<u8, Lsb0> {
+        let mut bits = BitVec::new();
+        for i in (0..len).rev() {
+            bits.push((code >> i) & 1 == 1);
+        }
+        bits
+    }
+
+    /// Entries sorted by `(length, tag)`: the order both the header is written in and the
+    /// order canonical codes were assigned in.
+    fn sorted_entries(&self) -> Vec<(String, u8)> {
+        let mut entries: Vec<(String, u8)> = self
+            .codes
+            .iter()
+            .map(|(tag, &(_, len))| (tag.clone(), len))
+            .collect();
+        entries.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+        entries
+    }
+
+    /// Serialize this instance's trained canonical code table (the same
+    /// `[num_symbols][(length, tag_len, tag_bytes)...]` header every blob embeds), so it can
+    /// be persisted and reloaded via `load_model` without retraining on the original corpus.
+    fn serialize_model(&self) -> Vec<u8> {
+        let entries = self.sorted_entries();
+        let mut out = Vec::new();
+        out.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+        for (tag, len) in &entries {
+            out.push(*len);
+            let tag_bytes = tag.as_bytes();
+            out.extend_from_slice(&(tag_bytes.len() as u16).to_le_bytes());
+            out.extend_from_slice(tag_bytes);
+        }
+        out
+    }
+
+    /// Inverse of `serialize_model`: the `(tag, length)` entries it wrote, in the same
+    /// order, advancing `pos` past the bytes consumed so callers parsing a full blob (where
+    /// the code table is just the leading section) can continue reading from where it left
+    /// off.
+    fn parse_model_entries(data: &[u8], pos: &mut usize) -> Result<Vec<(String, u8)>> {
+        let num_symbols = read_u32(data, pos)? as usize;
+        let mut entries = Vec::with_capacity(num_symbols);
+        for _ in 0..num_symbols {
+            let len = *data.get(*pos).context("Truncated symbol length")?;
+            *pos += 1;
+            let tag_len = read_u16(data, pos)? as usize;
+            let tag_bytes = data.get(*pos..*pos + tag_len).context("Truncated tag bytes")?;
+            *pos += tag_len;
+            let tag = String::from_utf8(tag_bytes.to_vec()).context("Tag was not valid UTF-8")?;
+            entries.push((tag, len));
+        }
+        Ok(entries)
+    }
+
+    fn encode_tags(&self, tags: &[String]) -> Result<Vec<u8>> {
+        let mut out = self.serialize_model();
+
+        out.extend_from_slice(&(tags.len() as u32).to_le_bytes());
+
+        let mut payload: BitVec<u8, Lsb0> = BitVec::new();
         for tag in tags {
-            let code = self.codes.get(tag)
+            let &(code, len) = self
+                .codes
+                .get(tag)
                 .with_context(|| format!("Tag not found in dictionary: {}", tag))?;
-            result.extend_from_bitslice(code);
+            payload.extend_from_bitslice(&Self::code_to_bits(code, len));
         }
-        
-        Ok(result)
+        out.extend(payload.into_vec());
+
+        Ok(out)
     }
-    
-    fn decode_tags(&self, bits: &BitSlice<u8, Lsb0>) -> Result<Vec<String>> {
-        let mut result = Vec::new();
+
+    /// Decode a blob produced by `encode_tags`, using only the embedded header — no
+    /// instance state is consulted, so this is an associated function rather than a method.
+    fn decode_tags(data: &[u8]) -> Result<Vec<String>> {
         let mut pos = 0;
-        
-        // Decode number of tags
-        if pos + 32 > bits.len() {
-            anyhow::bail!("Insufficient data for tag count");
-        }
-        let mut count_bytes = [0u8; 4];
-        for i in 0..4 {
-            if pos + i * 8 + 8 > bits.len() {
-                anyhow::bail!("Insufficient data for tag count");
-            }
-            count_bytes[i] = bits[pos + i*8..pos + (i+1)*8].load::<u8>();
-        }
-        let count = u32::from_le_bytes(count_bytes) as usize;
-        pos += 32;
-        
-        // Decode each tag
-        for _ in 0..count {
-            let mut current_code: BitVec<u8, Lsb0> = BitVec::new();
-            let mut found = false;
-            
-            // Try to match codes
-            for (code, tag) in &self.reverse_codes {
-                if pos + code.len() <= bits.len() {
-                    let slice = &bits[pos..pos + code.len()];
-                    if slice == code.as_bitslice() {
-                        result.push(tag.clone());
-                        pos += code.len();
-                        found = true;
-                        break;
-                    }
-                }
-            }
-            
-            if !found {
-                anyhow::bail!("Failed to decode tag at position {}", pos);
-            }
+        let entries = Self::parse_model_entries(data, &mut pos)?;
+
+        let num_tags = read_u32(data, &mut pos)? as usize;
+
+        let table = CanonicalDecodeTable::build(&entries);
+        let bits = data[pos..].view_bits::<Lsb0>();
+
+        let mut result = Vec::with_capacity(num_tags);
+        let mut bit_pos = 0;
+        for _ in 0..num_tags {
+            let (tag, consumed) = table.decode_one(bits, bit_pos)?;
+            bit_pos += consumed;
+            result.push(tag);
         }
-        
+
         Ok(result)
     }
 }
@@ -190,7 +372,7 @@ impl Compressor for HuffmanCompressor {
         let compressor = if self.codes.is_empty() {
             let mut new_compressor = HuffmanCompressor {
                 codes: self.codes.clone(),
-                reverse_codes: self.reverse_codes.clone(),
+                distinct_tags_estimate: self.distinct_tags_estimate,
             };
             new_compressor.build_from_corpus(tags)?;
             new_compressor
@@ -198,28 +380,44 @@ impl Compressor for HuffmanCompressor {
             // Corpus already built, use existing codes
             self.clone()
         };
-        
-        // Encode tags
-        let bits = compressor.encode_tags(tags)?;
-        
-        // Convert to bytes
-        Ok(bits.into_vec())
-    }
-    
+
+        let encoded = compressor.encode_tags(tags)?;
+
+        // Prefix with the method ID so the blob is self-describing
+        let mut out = vec![crate::compression::CompressionMethod::Huffman.id()];
+        out.extend(encoded);
+        Ok(out)
+    }
+
     fn decompress(&self, data: &[u8]) -> Result<Vec<String>> {
-        let bits = data.view_bits::<Lsb0>();
-        self.decode_tags(bits)
+        let payload = data.split_first().context("Empty Huffman blob")?.1;
+        Self::decode_tags(payload)
     }
-    
+
     fn algorithm_name(&self) -> &'static str {
         "huffman"
     }
+
+    fn distinct_tags_estimate(&self) -> Option<f64> {
+        self.distinct_tags_estimate
+    }
+
+    fn save_model(&self) -> Result<Vec<u8>> {
+        Ok(self.serialize_model())
+    }
+
+    fn load_model(&mut self, data: &[u8]) -> Result<()> {
+        let entries = Self::parse_model_entries(data, &mut 0)?;
+        self.codes = assign_canonical_codes(&entries);
+        self.distinct_tags_estimate = None;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    
+
     #[test]
     fn test_huffman_basic() {
         let tags = vec![
@@ -227,14 +425,93 @@ mod tests {
             "tag2".to_string(),
             "tag1".to_string(),
         ];
-        
+
         let mut compressor = HuffmanCompressor::new();
         compressor.build_from_corpus(&tags).unwrap();
-        
+
         let compressed = compressor.compress(&tags).unwrap();
         let decompressed = compressor.decompress(&compressed).unwrap();
-        
+
         assert_eq!(tags, decompressed);
     }
-}
 
+    #[test]
+    fn test_decompress_is_portable_across_instances() {
+        let tags = vec![
+            "tag1".to_string(),
+            "tag2".to_string(),
+            "tag3".to_string(),
+            "tag1".to_string(),
+            "tag1".to_string(),
+        ];
+
+        let mut compressor = HuffmanCompressor::new();
+        compressor.build_from_corpus(&tags).unwrap();
+        let compressed = compressor.compress(&tags).unwrap();
+
+        // A fresh, untrained compressor has no codes of its own, but the blob carries its
+        // own header, so it can still decode it.
+        let fresh = HuffmanCompressor::new();
+        let decompressed = fresh.decompress(&compressed).unwrap();
+
+        assert_eq!(tags, decompressed);
+    }
+
+    #[test]
+    fn test_single_symbol_corpus_round_trips() {
+        let tags = vec!["only_tag".to_string(); 3];
+
+        let mut compressor = HuffmanCompressor::new();
+        compressor.build_from_corpus(&tags).unwrap();
+        let compressed = compressor.compress(&tags).unwrap();
+        let decompressed = HuffmanCompressor::new().decompress(&compressed).unwrap();
+
+        assert_eq!(tags, decompressed);
+    }
+
+    #[test]
+    fn test_streaming_corpus_matches_exact_compression_ratio() {
+        let tags: Vec<String> = (0..500)
+            .map(|i| format!("tag{}", i % 20))
+            .collect();
+
+        let mut exact = HuffmanCompressor::new();
+        exact.build_from_corpus(&tags).unwrap();
+        let exact_compressed = exact.compress(&tags).unwrap();
+
+        let mut streaming = HuffmanCompressor::new();
+        streaming
+            .build_corpus_streaming(tags.clone().into_iter())
+            .unwrap();
+        let streaming_compressed = streaming.compress(&tags).unwrap();
+
+        assert_eq!(exact_compressed.len(), streaming_compressed.len());
+        assert_eq!(
+            streaming.decompress(&streaming_compressed).unwrap(),
+            tags
+        );
+
+        let estimate = streaming.distinct_tags_estimate().unwrap();
+        assert!((estimate - 20.0).abs() < 5.0, "estimate {estimate} far from 20");
+    }
+
+    #[test]
+    fn test_save_and_load_model_round_trips() {
+        let tags = vec![
+            "tag1".to_string(),
+            "tag2".to_string(),
+            "tag1".to_string(),
+            "tag3".to_string(),
+        ];
+
+        let mut trained = HuffmanCompressor::new();
+        trained.build_from_corpus(&tags).unwrap();
+        let model = trained.save_model().unwrap();
+
+        let mut restored = HuffmanCompressor::new();
+        restored.load_model(&model).unwrap();
+
+        let compressed = restored.compress(&tags).unwrap();
+        assert_eq!(restored.decompress(&compressed).unwrap(), tags);
+    }
+}
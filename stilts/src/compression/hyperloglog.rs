@@ -0,0 +1,157 @@
+//! HyperLogLog cardinality estimator
+//!
+//! Used by `HuffmanCompressor::build_corpus_streaming` to track how many distinct tags a
+//! streamed corpus contains without storing them, so callers get a sizing signal even when
+//! the full tag stream is too large to buffer or count exactly ahead of time.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// `2^precision` registers; 14 is a common default (16384 registers, ~0.8% standard error).
+const DEFAULT_PRECISION: u8 = 14;
+
+/// Estimates the number of distinct items added, using `O(2^precision)` memory regardless
+/// of how many items (or duplicates) are added.
+pub struct HyperLogLog {
+    precision: u8,
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    /// A new estimator using `DEFAULT_PRECISION`.
+    pub fn new() -> Self {
+        Self::with_precision(DEFAULT_PRECISION)
+    }
+
+    /// A new estimator with `2^precision` registers.
+    pub fn with_precision(precision: u8) -> Self {
+        let num_registers = 1usize << precision;
+        Self {
+            precision,
+            registers: vec![0u8; num_registers],
+        }
+    }
+
+    /// Add an item to the estimator.
+    pub fn add(&mut self, value: &str) {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        self.add_hash(hasher.finish());
+    }
+
+    /// Register `hash` against its bucket: the top `precision` bits select one of
+    /// `2^precision` registers, and the register stores the largest count of leading zeros
+    /// seen among the remaining bits (plus one) of any hash routed to it.
+    fn add_hash(&mut self, hash: u64) {
+        let precision = self.precision as u32;
+        let index = (hash >> (64 - precision)) as usize;
+        let remaining = hash << precision;
+        let max_rank = (64 - precision + 1) as u8;
+        let rank = ((remaining.leading_zeros() + 1) as u8).min(max_rank);
+        if rank > self.registers[index] {
+            self.registers[index] = rank;
+        }
+    }
+
+    /// Merge `other`'s registers into this one by taking the element-wise maximum, so two
+    /// estimators built over different shards (or in parallel) combine into a single
+    /// estimator over their union without re-adding any item. Both must share the same
+    /// precision (and therefore register count).
+    pub fn merge(&mut self, other: &Self) {
+        debug_assert_eq!(
+            self.precision, other.precision,
+            "cannot merge HyperLogLog estimators with different precision"
+        );
+        for (register, &other_register) in self.registers.iter_mut().zip(other.registers.iter()) {
+            if other_register > *register {
+                *register = other_register;
+            }
+        }
+    }
+
+    /// Estimate the number of distinct items added so far, via the harmonic-mean formula
+    /// with small-range (linear counting) and large-range corrections.
+    pub fn estimate(&self) -> f64 {
+        let num_registers = self.registers.len() as f64;
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / num_registers),
+        };
+
+        let inverse_sum: f64 = self
+            .registers
+            .iter()
+            .map(|&rank| 2f64.powi(-(rank as i32)))
+            .sum();
+        let raw_estimate = alpha * num_registers * num_registers / inverse_sum;
+
+        if raw_estimate <= 2.5 * num_registers {
+            let zero_registers = self.registers.iter().filter(|&&rank| rank == 0).count();
+            if zero_registers > 0 {
+                return num_registers * (num_registers / zero_registers as f64).ln();
+            }
+        }
+
+        let two_pow_64 = 2f64.powi(64);
+        if raw_estimate > two_pow_64 / 30.0 {
+            return -two_pow_64 * (1.0 - raw_estimate / two_pow_64).ln();
+        }
+
+        raw_estimate
+    }
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_within_error_bound() {
+        let mut hll = HyperLogLog::new();
+        for i in 0..10_000 {
+            hll.add(&format!("tag_{i}"));
+        }
+        let estimate = hll.estimate();
+        let error = (estimate - 10_000.0).abs() / 10_000.0;
+        assert!(error < 0.05, "estimate {estimate} too far from 10000");
+    }
+
+    #[test]
+    fn test_duplicates_do_not_inflate_estimate() {
+        let mut hll = HyperLogLog::new();
+        for _ in 0..10_000 {
+            hll.add("same_tag");
+        }
+        let estimate = hll.estimate();
+        assert!(estimate < 10.0, "estimate {estimate} should be near 1");
+    }
+
+    #[test]
+    fn test_merge_matches_single_estimator_over_union() {
+        let mut shard1 = HyperLogLog::new();
+        let mut shard2 = HyperLogLog::new();
+        let mut combined = HyperLogLog::new();
+        for i in 0..5_000 {
+            let tag = format!("tag_{i}");
+            shard1.add(&tag);
+            combined.add(&tag);
+        }
+        for i in 5_000..10_000 {
+            let tag = format!("tag_{i}");
+            shard2.add(&tag);
+            combined.add(&tag);
+        }
+
+        shard1.merge(&shard2);
+        let error = (shard1.estimate() - combined.estimate()).abs() / combined.estimate();
+        assert!(error < 0.01, "merged estimate {} too far from combined {}", shard1.estimate(), combined.estimate());
+    }
+}
@@ -2,10 +2,18 @@
 //! Compression algorithms for tag compression
 
 pub mod arithmetic;
+pub mod bloom;
 pub mod dictionary;
+pub mod fsst;
 pub mod huffman;
+pub mod hyperloglog;
+pub mod standard;
+pub mod streaming;
 
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
 
 /// Trait for compression algorithms
 pub trait Compressor: Send + Sync {
@@ -25,8 +33,175 @@ pub trait Compressor: Send + Sync {
 
     /// Get the algorithm name
     fn algorithm_name(&self) -> &'static str;
+
+    /// The HyperLogLog-estimated number of distinct tags seen while building this
+    /// compressor's corpus, for backends that built it via a streaming entry point (see
+    /// `HuffmanCompressor::build_corpus_streaming`). `None` for backends with no such
+    /// entry point, or if the corpus was built exactly instead.
+    fn distinct_tags_estimate(&self) -> Option<f64> {
+        None
+    }
+
+    /// Check whether `tag` is present in a blob previously produced by `compress`.
+    ///
+    /// The default implementation just decompresses and scans, which is correct for every
+    /// backend but does no better than O(n) in the compressed size. Backends with an index
+    /// structure that can answer membership without fully materializing the tag list (e.g.
+    /// `MappyTagStorage`'s probabilistic structure) should override this with a sublinear
+    /// check.
+    fn contains_tag(&self, compressed: &[u8], tag: &str) -> Result<bool> {
+        let tags = self.decompress(compressed)?;
+        Ok(tags.iter().any(|t| t == tag))
+    }
+
+    /// Serialize this compressor's trained model (code table / frequency table / symbol
+    /// table / dictionary) to bytes, so a corpus can be trained once, persisted, and reused
+    /// across processes via `load_model` instead of being rebuilt on every `compress` call.
+    /// The default is an empty model, appropriate for backends like the standard codec
+    /// wrappers that carry no corpus-derived state between calls.
+    fn save_model(&self) -> Result<Vec<u8>> {
+        Ok(Vec::new())
+    }
+
+    /// Restore a trained model previously produced by `save_model`. The default is a no-op,
+    /// matching `save_model`'s empty-model default.
+    fn load_model(&mut self, _data: &[u8]) -> Result<()> {
+        Ok(())
+    }
 }
 
 pub use arithmetic::ArithmeticCompressor;
+pub use bloom::BloomFilter;
 pub use dictionary::DictionaryCompressor;
+pub use fsst::FsstCompressor;
 pub use huffman::HuffmanCompressor;
+pub use hyperloglog::HyperLogLog;
+pub use streaming::{
+    DictionaryStreamingCompressor, DictionaryStreamingDecompressor, StreamingCompressor,
+    StreamingDecompressor,
+};
+pub use standard::CompressionMode;
+#[cfg(feature = "zstd-backend")]
+pub use standard::ZstdCompressor;
+#[cfg(feature = "brotli-backend")]
+pub use standard::BrotliCompressor;
+#[cfg(feature = "deflate-backend")]
+pub use standard::DeflateCompressor;
+#[cfg(feature = "lz4-backend")]
+pub use standard::Lz4Compressor;
+
+/// Stable 1-byte identifier for each `Compressor` backend. Every `Compressor::compress`
+/// prefixes its output with its method's `id()`, so a stored blob names the algorithm that
+/// produced it instead of requiring the caller to already hold the exact compressor. The one
+/// exception is `ArithmeticCompressor`, which encodes an empty tag list as an empty blob.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CompressionMethod {
+    Huffman,
+    Arithmetic,
+    Dictionary,
+    Fsst,
+    Zstd,
+    Brotli,
+    Deflate,
+    Lz4,
+}
+
+impl CompressionMethod {
+    /// The 1-byte ID prefixed to a `compress` output for this method.
+    pub fn id(self) -> u8 {
+        match self {
+            Self::Huffman => 0,
+            Self::Arithmetic => 1,
+            Self::Dictionary => 2,
+            Self::Fsst => 3,
+            Self::Zstd => 4,
+            Self::Brotli => 5,
+            Self::Deflate => 6,
+            Self::Lz4 => 7,
+        }
+    }
+
+    /// Recover the method from a prefix byte read off a compressed blob.
+    pub fn from_id(id: u8) -> Result<Self> {
+        match id {
+            0 => Ok(Self::Huffman),
+            1 => Ok(Self::Arithmetic),
+            2 => Ok(Self::Dictionary),
+            3 => Ok(Self::Fsst),
+            4 => Ok(Self::Zstd),
+            5 => Ok(Self::Brotli),
+            6 => Ok(Self::Deflate),
+            7 => Ok(Self::Lz4),
+            other => bail!("Unknown compression method ID: {}", other),
+        }
+    }
+}
+
+impl fmt::Display for CompressionMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Huffman => "huffman",
+            Self::Arithmetic => "arithmetic",
+            Self::Dictionary => "dictionary",
+            Self::Fsst => "fsst",
+            Self::Zstd => "zstd",
+            Self::Brotli => "brotli",
+            Self::Deflate => "deflate",
+            Self::Lz4 => "lz4",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for CompressionMethod {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "huffman" => Ok(Self::Huffman),
+            "arithmetic" => Ok(Self::Arithmetic),
+            "dictionary" => Ok(Self::Dictionary),
+            "fsst" => Ok(Self::Fsst),
+            "zstd" => Ok(Self::Zstd),
+            "brotli" => Ok(Self::Brotli),
+            "deflate" => Ok(Self::Deflate),
+            "lz4" => Ok(Self::Lz4),
+            other => bail!("Unknown compression method name: {}", other),
+        }
+    }
+}
+
+/// A set of already-configured `Compressor` backends keyed by `CompressionMethod`, so a
+/// self-describing blob produced by one of them can be decompressed via `decode_any`
+/// without the caller matching on the concrete type that made it.
+#[derive(Default)]
+pub struct Registry {
+    compressors: HashMap<CompressionMethod, Box<dyn Compressor>>,
+}
+
+impl Registry {
+    /// Create an empty registry; register backends with `register` before calling
+    /// `decode_any`.
+    pub fn new() -> Self {
+        Self {
+            compressors: HashMap::new(),
+        }
+    }
+
+    /// Register a trained (or default) compressor as the decoder for `method`.
+    pub fn register(&mut self, method: CompressionMethod, compressor: Box<dyn Compressor>) {
+        self.compressors.insert(method, compressor);
+    }
+
+    /// Read the leading method-ID byte off `data`, look up the compressor registered for
+    /// it, and decompress the (still-prefixed) blob.
+    pub fn decode_any(&self, data: &[u8]) -> Result<Vec<String>> {
+        let &id = data.first().context("Cannot decode an empty compressed blob")?;
+        let method = CompressionMethod::from_id(id)?;
+        let compressor = self
+            .compressors
+            .get(&method)
+            .with_context(|| format!("No compressor registered for method: {}", method))?;
+        compressor.decompress(data)
+    }
+}
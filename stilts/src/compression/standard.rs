@@ -0,0 +1,315 @@
+//! `Compressor` backends wrapping established general-purpose byte codecs (zstd, brotli,
+//! deflate/zlib, lz4), as a realistic baseline to benchmark the crate's bespoke entropy
+//! coders against, and a practical high-ratio option for large tag sets where a
+//! dictionary-style codec dominates. Each backend sits behind its own cargo feature so a
+//! default build doesn't pay for codecs it isn't using.
+
+use crate::compression::{CompressionMethod, Compressor};
+use anyhow::{Context, Result};
+
+/// Speed/ratio tradeoff knob, mapped to each codec's native compression level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressionMode {
+    Fast,
+    Balanced,
+    Best,
+}
+
+impl Default for CompressionMode {
+    fn default() -> Self {
+        Self::Balanced
+    }
+}
+
+/// Serialize a tag list as a length-prefixed UTF-8 byte stream, independent of any
+/// particular codec, so the same bytes can be handed to zstd/brotli/deflate/lz4 alike.
+fn serialize_tag_list(tags: &[String]) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(&(tags.len() as u32).to_le_bytes());
+    for tag in tags {
+        let bytes = tag.as_bytes();
+        out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+        out.extend_from_slice(bytes);
+    }
+    out
+}
+
+/// Inverse of `serialize_tag_list`.
+fn deserialize_tag_list(data: &[u8]) -> Result<Vec<String>> {
+    if data.len() < 4 {
+        anyhow::bail!("Insufficient data for tag count");
+    }
+    let count = u32::from_le_bytes(data[0..4].try_into().unwrap()) as usize;
+    let mut pos = 4;
+
+    let mut tags = Vec::with_capacity(count);
+    for _ in 0..count {
+        let len_bytes = data.get(pos..pos + 4).context("Insufficient data for tag length")?;
+        let len = u32::from_le_bytes(len_bytes.try_into().unwrap()) as usize;
+        pos += 4;
+        let tag_bytes = data.get(pos..pos + len).context("Truncated tag")?;
+        pos += len;
+        tags.push(String::from_utf8(tag_bytes.to_vec()).context("Tag is not valid UTF-8")?);
+    }
+
+    Ok(tags)
+}
+
+/// Wraps `zstd::stream::encode_all`/`decode_all` over the serialized tag list.
+#[cfg(feature = "zstd-backend")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ZstdCompressor {
+    mode: CompressionMode,
+}
+
+#[cfg(feature = "zstd-backend")]
+impl ZstdCompressor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_mode(mut self, mode: CompressionMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    fn level(&self) -> i32 {
+        match self.mode {
+            CompressionMode::Fast => 1,
+            CompressionMode::Balanced => 9,
+            CompressionMode::Best => 19,
+        }
+    }
+}
+
+#[cfg(feature = "zstd-backend")]
+impl Compressor for ZstdCompressor {
+    fn compress(&self, tags: &[String]) -> Result<Vec<u8>> {
+        let serialized = serialize_tag_list(tags);
+        let body =
+            zstd::stream::encode_all(serialized.as_slice(), self.level()).context("zstd compression failed")?;
+        let mut out = vec![CompressionMethod::Zstd.id()];
+        out.extend(body);
+        Ok(out)
+    }
+
+    fn decompress(&self, data: &[u8]) -> Result<Vec<String>> {
+        let payload = data.split_first().context("Empty zstd blob")?.1;
+        let serialized = zstd::stream::decode_all(payload).context("zstd decompression failed")?;
+        deserialize_tag_list(&serialized)
+    }
+
+    fn algorithm_name(&self) -> &'static str {
+        "zstd"
+    }
+}
+
+/// Wraps `brotli::BrotliCompress`/`BrotliDecompress` over the serialized tag list.
+#[cfg(feature = "brotli-backend")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BrotliCompressor {
+    mode: CompressionMode,
+}
+
+#[cfg(feature = "brotli-backend")]
+impl BrotliCompressor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_mode(mut self, mode: CompressionMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    fn quality(&self) -> i32 {
+        match self.mode {
+            CompressionMode::Fast => 2,
+            CompressionMode::Balanced => 6,
+            CompressionMode::Best => 11,
+        }
+    }
+}
+
+#[cfg(feature = "brotli-backend")]
+impl Compressor for BrotliCompressor {
+    fn compress(&self, tags: &[String]) -> Result<Vec<u8>> {
+        let serialized = serialize_tag_list(tags);
+        let mut params = brotli::enc::BrotliEncoderParams::default();
+        params.quality = self.quality();
+
+        let mut body = Vec::new();
+        brotli::BrotliCompress(&mut std::io::Cursor::new(serialized), &mut body, &params)
+            .context("brotli compression failed")?;
+
+        let mut out = vec![CompressionMethod::Brotli.id()];
+        out.extend(body);
+        Ok(out)
+    }
+
+    fn decompress(&self, data: &[u8]) -> Result<Vec<String>> {
+        let payload = data.split_first().context("Empty brotli blob")?.1;
+        let mut serialized = Vec::new();
+        brotli::BrotliDecompress(&mut std::io::Cursor::new(payload), &mut serialized)
+            .context("brotli decompression failed")?;
+        deserialize_tag_list(&serialized)
+    }
+
+    fn algorithm_name(&self) -> &'static str {
+        "brotli"
+    }
+}
+
+/// Wraps `flate2`'s zlib encoder/decoder over the serialized tag list.
+#[cfg(feature = "deflate-backend")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DeflateCompressor {
+    mode: CompressionMode,
+}
+
+#[cfg(feature = "deflate-backend")]
+impl DeflateCompressor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_mode(mut self, mode: CompressionMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    fn level(&self) -> flate2::Compression {
+        match self.mode {
+            CompressionMode::Fast => flate2::Compression::fast(),
+            CompressionMode::Balanced => flate2::Compression::default(),
+            CompressionMode::Best => flate2::Compression::best(),
+        }
+    }
+}
+
+#[cfg(feature = "deflate-backend")]
+impl Compressor for DeflateCompressor {
+    fn compress(&self, tags: &[String]) -> Result<Vec<u8>> {
+        use std::io::Write;
+
+        let serialized = serialize_tag_list(tags);
+        let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), self.level());
+        encoder.write_all(&serialized).context("deflate compression failed")?;
+        let body = encoder.finish().context("deflate compression failed")?;
+
+        let mut out = vec![CompressionMethod::Deflate.id()];
+        out.extend(body);
+        Ok(out)
+    }
+
+    fn decompress(&self, data: &[u8]) -> Result<Vec<String>> {
+        use std::io::Read;
+
+        let payload = data.split_first().context("Empty deflate blob")?.1;
+        let mut decoder = flate2::read::ZlibDecoder::new(payload);
+        let mut serialized = Vec::new();
+        decoder.read_to_end(&mut serialized).context("deflate decompression failed")?;
+        deserialize_tag_list(&serialized)
+    }
+
+    fn algorithm_name(&self) -> &'static str {
+        "deflate"
+    }
+}
+
+/// Wraps `lz4::block::compress`/`decompress` over the serialized tag list. The
+/// uncompressed size is prepended by the `lz4` crate itself, so `decompress` doesn't need
+/// the caller to already know the original length.
+#[cfg(feature = "lz4-backend")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Lz4Compressor {
+    mode: CompressionMode,
+}
+
+#[cfg(feature = "lz4-backend")]
+impl Lz4Compressor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_mode(mut self, mode: CompressionMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    fn block_mode(&self) -> lz4::block::CompressionMode {
+        match self.mode {
+            CompressionMode::Fast => lz4::block::CompressionMode::FAST(8),
+            CompressionMode::Balanced => lz4::block::CompressionMode::DEFAULT,
+            CompressionMode::Best => lz4::block::CompressionMode::HIGHCOMPRESSION(12),
+        }
+    }
+}
+
+#[cfg(feature = "lz4-backend")]
+impl Compressor for Lz4Compressor {
+    fn compress(&self, tags: &[String]) -> Result<Vec<u8>> {
+        let serialized = serialize_tag_list(tags);
+        let body = lz4::block::compress(&serialized, Some(self.block_mode()), true)
+            .context("lz4 compression failed")?;
+
+        let mut out = vec![CompressionMethod::Lz4.id()];
+        out.extend(body);
+        Ok(out)
+    }
+
+    fn decompress(&self, data: &[u8]) -> Result<Vec<String>> {
+        let payload = data.split_first().context("Empty lz4 blob")?.1;
+        let serialized = lz4::block::decompress(payload, None).context("lz4 decompression failed")?;
+        deserialize_tag_list(&serialized)
+    }
+
+    fn algorithm_name(&self) -> &'static str {
+        "lz4"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "zstd-backend")]
+    #[test]
+    fn test_zstd_round_trip() {
+        let tags = vec!["tag1".to_string(), "tag2".to_string(), "tag1".to_string()];
+        let compressor = ZstdCompressor::new().with_mode(CompressionMode::Best);
+        let compressed = compressor.compress(&tags).unwrap();
+        let decompressed = compressor.decompress(&compressed).unwrap();
+        assert_eq!(tags, decompressed);
+    }
+
+    #[cfg(feature = "brotli-backend")]
+    #[test]
+    fn test_brotli_round_trip() {
+        let tags = vec!["tag1".to_string(), "tag2".to_string(), "tag1".to_string()];
+        let compressor = BrotliCompressor::new();
+        let compressed = compressor.compress(&tags).unwrap();
+        let decompressed = compressor.decompress(&compressed).unwrap();
+        assert_eq!(tags, decompressed);
+    }
+
+    #[cfg(feature = "deflate-backend")]
+    #[test]
+    fn test_deflate_round_trip() {
+        let tags = vec!["tag1".to_string(), "tag2".to_string(), "tag1".to_string()];
+        let compressor = DeflateCompressor::new();
+        let compressed = compressor.compress(&tags).unwrap();
+        let decompressed = compressor.decompress(&compressed).unwrap();
+        assert_eq!(tags, decompressed);
+    }
+
+    #[cfg(feature = "lz4-backend")]
+    #[test]
+    fn test_lz4_round_trip() {
+        let tags = vec!["tag1".to_string(), "tag2".to_string(), "tag1".to_string()];
+        let compressor = Lz4Compressor::new();
+        let compressed = compressor.compress(&tags).unwrap();
+        let decompressed = compressor.decompress(&compressed).unwrap();
+        assert_eq!(tags, decompressed);
+    }
+}
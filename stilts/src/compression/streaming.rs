@@ -0,0 +1,204 @@
+#![allow(clippy::cast_precision_loss)] // Acceptable for compression ratio calculations
+//! Streaming compression with cross-block dictionary carryover
+//!
+//! The plain `Compressor` trait is one-shot: every call to `compress` either trains a
+//! fresh dictionary or re-embeds one, so a long sequence of small tag batches re-pays
+//! that overhead on every block and can't exploit redundancy across batches. The traits
+//! here let a compressor retain state (a dictionary, for LZ-style backends also a window
+//! of recently seen tags) between calls, so later blocks reference symbols established by
+//! earlier ones — similar to an LZ4 streaming compressor's dictionary continuation.
+
+use anyhow::{Context, Result};
+
+use crate::compression::dictionary::DictionaryCompressor;
+
+/// Compresses a sequence of tag blocks while retaining dictionary state between calls.
+pub trait StreamingCompressor {
+    /// Compress one block, appending its encoded bytes to `out`.
+    fn next_block(&mut self, tags: &[String], out: &mut Vec<u8>) -> Result<()>;
+
+    /// Drop all retained state. The next `next_block` call starts an independent frame
+    /// that a decompressor must also have `reset` before it can read.
+    fn reset(&mut self);
+}
+
+/// Decompresses blocks produced by a matching `StreamingCompressor`, threading the same
+/// retained state across calls.
+pub trait StreamingDecompressor {
+    /// Decompress one block.
+    fn next_block(&mut self, data: &[u8]) -> Result<Vec<String>>;
+
+    /// Drop all retained state, matching a `StreamingCompressor::reset` call.
+    fn reset(&mut self);
+}
+
+/// Streaming wrapper around `DictionaryCompressor`. Each block carries only the dictionary
+/// entries introduced since the previous block (a small continuation header), followed by
+/// the code stream for this block's tags — the full dictionary is never retransmitted.
+pub struct DictionaryStreamingCompressor {
+    dict: DictionaryCompressor,
+}
+
+impl DictionaryStreamingCompressor {
+    pub fn new() -> Self {
+        Self {
+            dict: DictionaryCompressor::new(),
+        }
+    }
+}
+
+impl Default for DictionaryStreamingCompressor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StreamingCompressor for DictionaryStreamingCompressor {
+    fn next_block(&mut self, tags: &[String], out: &mut Vec<u8>) -> Result<()> {
+        let new_entries = self.dict.learn(tags);
+
+        out.extend_from_slice(&(new_entries.len() as u32).to_le_bytes());
+        for (tag, code) in &new_entries {
+            let tag_bytes = tag.as_bytes();
+            out.extend_from_slice(&(tag_bytes.len() as u32).to_le_bytes());
+            out.extend_from_slice(tag_bytes);
+            out.extend_from_slice(&code.to_le_bytes());
+        }
+
+        let payload = self.dict.compress_payload(tags)?;
+        out.extend_from_slice(&payload);
+        Ok(())
+    }
+
+    fn reset(&mut self) {
+        self.dict = DictionaryCompressor::new();
+    }
+}
+
+/// Matching decompressor for `DictionaryStreamingCompressor`.
+pub struct DictionaryStreamingDecompressor {
+    dict: DictionaryCompressor,
+}
+
+impl DictionaryStreamingDecompressor {
+    pub fn new() -> Self {
+        Self {
+            dict: DictionaryCompressor::new(),
+        }
+    }
+}
+
+impl Default for DictionaryStreamingDecompressor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StreamingDecompressor for DictionaryStreamingDecompressor {
+    fn next_block(&mut self, data: &[u8]) -> Result<Vec<String>> {
+        let count_bytes: [u8; 4] = data
+            .get(0..4)
+            .context("Truncated streaming block header")?
+            .try_into()?;
+        let new_entry_count = u32::from_le_bytes(count_bytes) as usize;
+        let mut pos = 4;
+
+        for _ in 0..new_entry_count {
+            let len_bytes: [u8; 4] = data
+                .get(pos..pos + 4)
+                .context("Truncated streaming entry length")?
+                .try_into()?;
+            let len = u32::from_le_bytes(len_bytes) as usize;
+            pos += 4;
+
+            let tag_bytes = data
+                .get(pos..pos + len)
+                .context("Truncated streaming entry tag")?;
+            let tag = String::from_utf8(tag_bytes.to_vec())?;
+            pos += len;
+
+            let code_bytes: [u8; 4] = data
+                .get(pos..pos + 4)
+                .context("Truncated streaming entry code")?
+                .try_into()?;
+            let code = u32::from_le_bytes(code_bytes);
+            pos += 4;
+
+            self.dict.learn_entry(tag, code);
+        }
+
+        self.dict.decompress_payload(&data[pos..])
+    }
+
+    fn reset(&mut self) {
+        self.dict = DictionaryCompressor::new();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_streaming_round_trip_across_blocks() {
+        let mut compressor = DictionaryStreamingCompressor::new();
+        let mut decompressor = DictionaryStreamingDecompressor::new();
+
+        let block1 = vec!["cat".to_string(), "dog".to_string()];
+        let block2 = vec!["dog".to_string(), "bird".to_string(), "cat".to_string()];
+
+        let mut encoded1 = Vec::new();
+        compressor.next_block(&block1, &mut encoded1).unwrap();
+        assert_eq!(decompressor.next_block(&encoded1).unwrap(), block1);
+
+        let mut encoded2 = Vec::new();
+        compressor.next_block(&block2, &mut encoded2).unwrap();
+        assert_eq!(decompressor.next_block(&encoded2).unwrap(), block2);
+    }
+
+    #[test]
+    fn test_streaming_later_block_omits_known_tags_from_header() {
+        let mut compressor = DictionaryStreamingCompressor::new();
+
+        let mut encoded1 = Vec::new();
+        compressor
+            .next_block(&["repeat".to_string()], &mut encoded1)
+            .unwrap();
+
+        let mut encoded2 = Vec::new();
+        compressor
+            .next_block(&["repeat".to_string()], &mut encoded2)
+            .unwrap();
+
+        // Second block re-uses the already-learned code, so its continuation header
+        // carries zero new entries.
+        assert_eq!(&encoded2[0..4], &0u32.to_le_bytes());
+        assert!(encoded2.len() < encoded1.len());
+    }
+
+    #[test]
+    fn test_streaming_reset_starts_independent_frame() {
+        let mut compressor = DictionaryStreamingCompressor::new();
+        let mut decompressor = DictionaryStreamingDecompressor::new();
+
+        let mut encoded = Vec::new();
+        compressor
+            .next_block(&["only".to_string()], &mut encoded)
+            .unwrap();
+        decompressor.next_block(&encoded).unwrap();
+
+        compressor.reset();
+        decompressor.reset();
+
+        let mut reset_encoded = Vec::new();
+        compressor
+            .next_block(&["only".to_string()], &mut reset_encoded)
+            .unwrap();
+        // After a reset, "only" is relearned from scratch, so the header has one new entry again.
+        assert_eq!(&reset_encoded[0..4], &1u32.to_le_bytes());
+        assert_eq!(
+            decompressor.next_block(&reset_encoded).unwrap(),
+            vec!["only".to_string()]
+        );
+    }
+}
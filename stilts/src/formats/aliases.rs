@@ -0,0 +1,244 @@
+//! Synonym/alias tables and split-concat query expansion for `TagParser::expand`.
+//!
+//! Tag corpora commonly contain near-duplicate tags (`canine`/`canid`,
+//! `teeth`/`sharp_teeth`, `traditionalmedia(artwork)` vs `traditional_media`) that hurt
+//! exact-string similarity metrics like Jaccard because they're treated as disjoint. This
+//! module normalizes a tag to a canonical form (via an explicit alias table) or expands it
+//! into a small set of equivalent forms (aliases, plus a split or concatenated variant of
+//! compound words) for callers that want an OR-of-equivalents rather than a single
+//! normalized string.
+
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+
+/// Default number of pieces a compound tag may be broken into when looking for a split
+/// form; bounds the combinatorial search over possible split points.
+const DEFAULT_MAX_SPLIT_DEPTH: usize = 2;
+
+/// A canonical-tag -> aliases table, plus an optional vocabulary used to validate
+/// split/concat forms of compound tags. Load one with `from_json`, or build one in code
+/// and add a vocabulary with `with_vocabulary`.
+#[derive(Debug, Clone, Default)]
+pub struct AliasTable {
+    /// Every known tag (canonical or alias) mapped to its canonical representative.
+    canonical_for: HashMap<String, String>,
+    /// Known tags consulted when splitting a compound tag into consecutive words, or when
+    /// validating a concatenated form.
+    vocabulary: HashSet<String>,
+}
+
+impl AliasTable {
+    /// An empty alias table with no vocabulary.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load an alias table from a JSON object mapping each canonical tag to its aliases,
+    /// e.g. `{"canine": ["canid"], "sharp_teeth": ["teeth"]}`.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let raw: HashMap<String, Vec<String>> = serde_json::from_str(json)?;
+        let mut table = Self::new();
+        for (canonical, aliases) in raw {
+            table
+                .canonical_for
+                .insert(canonical.clone(), canonical.clone());
+            for alias in aliases {
+                table.canonical_for.insert(alias, canonical.clone());
+            }
+        }
+        Ok(table)
+    }
+
+    /// Supply the vocabulary consulted when splitting or concatenating compound tags
+    /// (e.g. to confirm that "sharpteeth" splits into the two known words "sharp"/"teeth").
+    pub fn with_vocabulary(mut self, vocabulary: impl IntoIterator<Item = String>) -> Self {
+        self.vocabulary = vocabulary.into_iter().collect();
+        self
+    }
+
+    /// The canonical representative for `tag`: an explicit alias entry if one exists,
+    /// otherwise the canonical form of its concatenated or split variant if that's known,
+    /// otherwise `tag` itself when no normalization is possible.
+    pub fn canonicalize(&self, tag: &str) -> String {
+        if let Some(canonical) = self.canonical_for.get(tag) {
+            return canonical.clone();
+        }
+
+        if tag.contains('_') {
+            let concat = tag.replace('_', "");
+            if let Some(canonical) = self.canonical_for.get(&concat) {
+                return canonical.clone();
+            }
+        } else {
+            for split in split_candidates(tag, &self.vocabulary, DEFAULT_MAX_SPLIT_DEPTH) {
+                if let Some(canonical) = self.canonical_for.get(&split) {
+                    return canonical.clone();
+                }
+            }
+        }
+
+        tag.to_string()
+    }
+
+    /// Known aliases sharing `tag`'s canonical form, not including `tag` itself.
+    fn known_equivalents(&self, tag: &str) -> Vec<String> {
+        let canonical = self
+            .canonical_for
+            .get(tag)
+            .cloned()
+            .unwrap_or_else(|| tag.to_string());
+
+        self.canonical_for
+            .iter()
+            .filter(|(_, c)| **c == canonical)
+            .map(|(alias, _)| alias.clone())
+            .filter(|alias| alias != tag)
+            .collect()
+    }
+
+    /// Expand a single query term into its OR-alternatives: itself, any known aliases, and
+    /// a split or concatenated form (whichever applies to `tag`'s shape). `max_split_depth`
+    /// bounds how many pieces a compound tag may be broken into, to keep the alternative
+    /// set small rather than enumerating every possible split.
+    pub fn expand_term(&self, tag: &str, max_split_depth: usize) -> Vec<String> {
+        let mut forms = vec![tag.to_string()];
+        for equivalent in self.known_equivalents(tag) {
+            if !forms.contains(&equivalent) {
+                forms.push(equivalent);
+            }
+        }
+
+        if tag.contains('_') {
+            let concat = tag.replace('_', "");
+            if !forms.contains(&concat) {
+                forms.push(concat);
+            }
+        } else {
+            for split in split_candidates(tag, &self.vocabulary, max_split_depth) {
+                if !forms.contains(&split) {
+                    forms.push(split);
+                }
+            }
+        }
+
+        forms
+    }
+}
+
+/// Try to break `tag` into consecutive known-vocabulary words, e.g.
+/// `"sharpteeth"` -> `["sharp_teeth"]`, joined with `_` to match this corpus's multi-word
+/// tag convention. Recursion is bounded by `max_depth` pieces to avoid combinatorial
+/// blowup on long strings.
+fn split_candidates(tag: &str, vocabulary: &HashSet<String>, max_depth: usize) -> Vec<String> {
+    fn helper(
+        remaining: &str,
+        vocabulary: &HashSet<String>,
+        max_depth: usize,
+        current: &mut Vec<String>,
+        found: &mut Vec<Vec<String>>,
+    ) {
+        if remaining.is_empty() {
+            if current.len() > 1 {
+                found.push(current.clone());
+            }
+            return;
+        }
+        if current.len() >= max_depth {
+            return;
+        }
+
+        // Candidate split points are char boundaries only: raw byte offsets (as
+        // `1..=remaining.len()` would give) panic in `str::split_at` the moment `remaining`
+        // contains a multi-byte UTF-8 character.
+        let mut split_points: Vec<usize> = remaining.char_indices().map(|(i, _)| i).skip(1).collect();
+        split_points.push(remaining.len());
+
+        for split_at in split_points {
+            let (head, tail) = remaining.split_at(split_at);
+            if vocabulary.contains(head) {
+                current.push(head.to_string());
+                helper(tail, vocabulary, max_depth, current, found);
+                current.pop();
+            }
+        }
+    }
+
+    let mut found = Vec::new();
+    helper(tag, vocabulary, max_depth, &mut Vec::new(), &mut found);
+    found.into_iter().map(|parts| parts.join("_")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alias_lookup_both_directions() {
+        let table = AliasTable::from_json(r#"{"canine": ["canid"]}"#).unwrap();
+        assert_eq!(table.canonicalize("canid"), "canine");
+        assert_eq!(table.canonicalize("canine"), "canine");
+    }
+
+    #[test]
+    fn test_expand_term_includes_aliases() {
+        let table = AliasTable::from_json(r#"{"canine": ["canid"]}"#).unwrap();
+        let mut forms = table.expand_term("canine", 2);
+        forms.sort();
+        assert_eq!(forms, vec!["canid".to_string(), "canine".to_string()]);
+    }
+
+    #[test]
+    fn test_split_form_against_vocabulary() {
+        let vocabulary = ["sharp".to_string(), "teeth".to_string()];
+        let table = AliasTable::new().with_vocabulary(vocabulary);
+        let forms = table.expand_term("sharpteeth", 2);
+        assert!(forms.contains(&"sharp_teeth".to_string()));
+    }
+
+    #[test]
+    fn test_concat_form_from_underscored_tag() {
+        let table = AliasTable::new();
+        let forms = table.expand_term("sharp_teeth", 2);
+        assert!(forms.contains(&"sharpteeth".to_string()));
+    }
+
+    #[test]
+    fn test_canonicalize_via_split_form() {
+        let vocabulary = ["sharp".to_string(), "teeth".to_string()];
+        let table = AliasTable::from_json(r#"{"sharp_teeth": ["teeth"]}"#)
+            .unwrap()
+            .with_vocabulary(vocabulary);
+        assert_eq!(table.canonicalize("sharpteeth"), "sharp_teeth");
+    }
+
+    #[test]
+    fn test_max_split_depth_bounds_pieces() {
+        let vocabulary = ["a".to_string(), "b".to_string(), "c".to_string()];
+        let table = AliasTable::new().with_vocabulary(vocabulary);
+        // "abc" needs 3 pieces to fully split into "a"/"b"/"c"; depth 2 must not find it.
+        assert!(split_candidates("abc", &table.vocabulary, 2).is_empty());
+        assert!(!split_candidates("abc", &table.vocabulary, 3).is_empty());
+    }
+
+    #[test]
+    fn test_split_candidates_does_not_panic_on_multi_byte_chars() {
+        // `split_at` panics on a non-char-boundary byte index; `helper` used to iterate raw
+        // byte offsets (`1..=remaining.len()`), which hits one the moment a tag contains a
+        // multi-byte UTF-8 character, even with no matching vocabulary entries.
+        let vocabulary: HashSet<String> = HashSet::new();
+        assert!(split_candidates("日本語", &vocabulary, 2).is_empty());
+
+        let table = AliasTable::new();
+        let forms = table.expand_term("日本語", 2);
+        assert_eq!(forms, vec!["日本語".to_string()]);
+    }
+
+    #[test]
+    fn test_split_candidates_finds_multi_byte_vocabulary_words() {
+        let vocabulary: HashSet<String> = ["日本".to_string(), "語".to_string()]
+            .into_iter()
+            .collect();
+        let forms = split_candidates("日本語", &vocabulary, 2);
+        assert!(forms.contains(&"日本_語".to_string()));
+    }
+}
@@ -1,7 +1,12 @@
 //! Tag format parsers and serializers
 
+pub mod aliases;
 pub mod parser;
 pub mod serializer;
 
-pub use parser::{CommaSeparatedParser, JsonParser, SpaceSeparatedParser, TagParser};
-pub use serializer::TagSerializer;
+pub use aliases::AliasTable;
+pub use parser::{
+    CaptionParser, CommaSeparatedParser, CsvParser, JsonParser, SpaceSeparatedParser, TagParser,
+    parser_for,
+};
+pub use serializer::{CborSerializer, TagDeserializer, TagSerializer};
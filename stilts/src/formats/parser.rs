@@ -1,15 +1,32 @@
 //! Tag format parsers
 
+use crate::formats::aliases::AliasTable;
 use anyhow::Result;
 use serde_json;
 
+/// Default max split depth used by `TagParser::expand`'s default implementation; see
+/// `AliasTable::expand_term` for what this bounds.
+const DEFAULT_EXPAND_SPLIT_DEPTH: usize = 2;
+
 /// Trait for parsing tag formats
 pub trait TagParser: Send + Sync {
     /// Parse input string into a vector of tags
     fn parse(&self, input: &str) -> Result<Vec<String>>;
-    
+
     /// Get the format name
     fn format_name(&self) -> &'static str;
+
+    /// Expand parsed tags into an AND-of-ORs query-expansion tree: each tag becomes a list
+    /// of equivalent forms (itself, known aliases, and a split or concatenated variant) any
+    /// one of which should count as a match, so approximate retrieval isn't defeated by
+    /// near-duplicate tags that don't share an exact string (`canine`/`canid`,
+    /// `sharp_teeth`/`teeth`). The outer `Vec` is the AND across tags; each inner `Vec` is
+    /// the OR across that tag's equivalents.
+    fn expand(&self, tags: &[String], alias_table: &AliasTable) -> Vec<Vec<String>> {
+        tags.iter()
+            .map(|tag| alias_table.expand_term(tag, DEFAULT_EXPAND_SPLIT_DEPTH))
+            .collect()
+    }
 }
 
 /// Space-separated tag parser
@@ -96,6 +113,127 @@ impl TagParser for JsonParser {
     }
 }
 
+/// RFC-4180-quoted CSV parser: a comma inside a `"..."` field does not split the tag,
+/// and `""` inside a quoted field is an escaped literal quote.
+pub struct CsvParser;
+
+impl CsvParser {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Split a single CSV row into fields, honoring quoting.
+    fn parse_row(row: &str) -> Vec<String> {
+        let mut fields = Vec::new();
+        let mut field = String::new();
+        let mut in_quotes = false;
+        let mut chars = row.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '"' if in_quotes && chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = !in_quotes,
+                ',' if !in_quotes => {
+                    fields.push(std::mem::take(&mut field));
+                }
+                _ => field.push(c),
+            }
+        }
+        fields.push(field);
+
+        fields
+    }
+}
+
+impl Default for CsvParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TagParser for CsvParser {
+    fn parse(&self, input: &str) -> Result<Vec<String>> {
+        Ok(input
+            .lines()
+            .flat_map(Self::parse_row)
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect())
+    }
+
+    fn format_name(&self) -> &'static str {
+        "csv"
+    }
+}
+
+/// Parser for the common training-caption convention: tags are comma- or
+/// space-separated and underscores stand in for spaces (e.g. `scott_pilgrim`).
+/// Set `round_trip` to keep the underscores as-is instead of expanding them,
+/// for callers that need to re-serialize tags back into the same convention.
+pub struct CaptionParser {
+    round_trip: bool,
+}
+
+impl CaptionParser {
+    pub fn new() -> Self {
+        Self { round_trip: false }
+    }
+
+    /// Keep underscores in parsed tags instead of expanding them to spaces.
+    pub fn with_round_trip(round_trip: bool) -> Self {
+        Self { round_trip }
+    }
+}
+
+impl Default for CaptionParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TagParser for CaptionParser {
+    fn parse(&self, input: &str) -> Result<Vec<String>> {
+        let raw: Vec<&str> = if input.contains(',') {
+            input.split(',').collect()
+        } else {
+            input.split_whitespace().collect()
+        };
+
+        Ok(raw
+            .into_iter()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| {
+                if self.round_trip {
+                    s.to_string()
+                } else {
+                    s.replace('_', " ")
+                }
+            })
+            .collect())
+    }
+
+    fn format_name(&self) -> &'static str {
+        "caption"
+    }
+}
+
+/// Construct a `TagParser` by name, for callers (server endpoints, benchmarks) that
+/// select a format from user input rather than hardcoding a concrete type.
+pub fn parser_for(name: &str) -> Option<Box<dyn TagParser>> {
+    match name {
+        "space" => Some(Box::new(SpaceSeparatedParser::new())),
+        "comma" => Some(Box::new(CommaSeparatedParser::new())),
+        "json" => Some(Box::new(JsonParser::new())),
+        "csv" => Some(Box::new(CsvParser::new())),
+        "caption" => Some(Box::new(CaptionParser::new())),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -120,5 +258,52 @@ mod tests {
         let tags = parser.parse(r#"["tag1","tag2","tag3"]"#).unwrap();
         assert_eq!(tags, vec!["tag1", "tag2", "tag3"]);
     }
+
+    #[test]
+    fn test_csv_quoted_comma() {
+        let parser = CsvParser::new();
+        let tags = parser
+            .parse(r#"tag1,"scott_pilgrim,_the_movie",tag3"#)
+            .unwrap();
+        assert_eq!(tags, vec!["tag1", "scott_pilgrim,_the_movie", "tag3"]);
+    }
+
+    #[test]
+    fn test_csv_escaped_quote() {
+        let parser = CsvParser::new();
+        let tags = parser.parse(r#""say ""hi""",tag2"#).unwrap();
+        assert_eq!(tags, vec![r#"say "hi""#, "tag2"]);
+    }
+
+    #[test]
+    fn test_caption_expands_underscores() {
+        let parser = CaptionParser::new();
+        let tags = parser.parse("scott_pilgrim, the_movie").unwrap();
+        assert_eq!(tags, vec!["scott pilgrim", "the movie"]);
+    }
+
+    #[test]
+    fn test_caption_round_trip_keeps_underscores() {
+        let parser = CaptionParser::with_round_trip(true);
+        let tags = parser.parse("scott_pilgrim the_movie").unwrap();
+        assert_eq!(tags, vec!["scott_pilgrim", "the_movie"]);
+    }
+
+    #[test]
+    fn test_parser_for() {
+        assert!(parser_for("csv").is_some());
+        assert!(parser_for("caption").is_some());
+        assert!(parser_for("nonexistent").is_none());
+    }
+
+    #[test]
+    fn test_expand_uses_default_depth() {
+        let parser = SpaceSeparatedParser::new();
+        let tags = parser.parse("canine").unwrap();
+        let aliases = AliasTable::from_json(r#"{"canine": ["canid"]}"#).unwrap();
+        let expanded = parser.expand(&tags, &aliases);
+        assert_eq!(expanded.len(), 1);
+        assert!(expanded[0].contains(&"canid".to_string()));
+    }
 }
 
@@ -7,7 +7,24 @@ use serde_json;
 pub trait TagSerializer: Send + Sync {
     /// Serialize tags to a string
     fn serialize(&self, tags: &[String]) -> Result<String>;
-    
+
+    /// Serialize tags to bytes. Defaults to UTF-8-encoding `serialize`'s output, which is
+    /// correct for every text format; binary formats (e.g. CBOR) override this directly
+    /// and skip the intermediate `String`.
+    fn serialize_bytes(&self, tags: &[String]) -> Result<Vec<u8>> {
+        Ok(self.serialize(tags)?.into_bytes())
+    }
+
+    /// Get the format name
+    fn format_name(&self) -> &'static str;
+}
+
+/// Trait for deserializing tags back out of a format produced by the matching
+/// `TagSerializer`, so each format round-trips rather than being write-only.
+pub trait TagDeserializer: Send + Sync {
+    /// Deserialize tags from bytes.
+    fn deserialize(&self, data: &[u8]) -> Result<Vec<String>>;
+
     /// Get the format name
     fn format_name(&self) -> &'static str;
 }
@@ -31,7 +48,18 @@ impl TagSerializer for SpaceSeparatedSerializer {
     fn serialize(&self, tags: &[String]) -> Result<String> {
         Ok(tags.join(" "))
     }
-    
+
+    fn format_name(&self) -> &'static str {
+        "space-separated"
+    }
+}
+
+impl TagDeserializer for SpaceSeparatedSerializer {
+    fn deserialize(&self, data: &[u8]) -> Result<Vec<String>> {
+        let text = std::str::from_utf8(data)?;
+        Ok(text.split_whitespace().map(str::to_string).collect())
+    }
+
     fn format_name(&self) -> &'static str {
         "space-separated"
     }
@@ -56,7 +84,21 @@ impl TagSerializer for CommaSeparatedSerializer {
     fn serialize(&self, tags: &[String]) -> Result<String> {
         Ok(tags.join(","))
     }
-    
+
+    fn format_name(&self) -> &'static str {
+        "comma-separated"
+    }
+}
+
+impl TagDeserializer for CommaSeparatedSerializer {
+    fn deserialize(&self, data: &[u8]) -> Result<Vec<String>> {
+        let text = std::str::from_utf8(data)?;
+        if text.is_empty() {
+            return Ok(Vec::new());
+        }
+        Ok(text.split(',').map(str::to_string).collect())
+    }
+
     fn format_name(&self) -> &'static str {
         "comma-separated"
     }
@@ -81,10 +123,110 @@ impl TagSerializer for JsonSerializer {
     fn serialize(&self, tags: &[String]) -> Result<String> {
         Ok(serde_json::to_string(tags)?)
     }
-    
+
+    fn format_name(&self) -> &'static str {
+        "json"
+    }
+}
+
+impl TagDeserializer for JsonSerializer {
+    fn deserialize(&self, data: &[u8]) -> Result<Vec<String>> {
+        Ok(serde_json::from_slice(data)?)
+    }
+
     fn format_name(&self) -> &'static str {
         "json"
     }
 }
 
+/// CBOR serializer. Self-describing binary encoding that is smaller than JSON (each tag
+/// costs a short length prefix instead of two quotes and a separator) and, unlike the
+/// comma/space formats, never needs escaping since it doesn't rely on a delimiter character
+/// that could appear inside a tag.
+pub struct CborSerializer;
+
+impl Default for CborSerializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
+impl CborSerializer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl TagSerializer for CborSerializer {
+    fn serialize(&self, _tags: &[String]) -> Result<String> {
+        anyhow::bail!("CBOR is a binary format; use serialize_bytes instead of serialize")
+    }
+
+    fn serialize_bytes(&self, tags: &[String]) -> Result<Vec<u8>> {
+        let mut out = Vec::new();
+        ciborium::into_writer(tags, &mut out)?;
+        Ok(out)
+    }
+
+    fn format_name(&self) -> &'static str {
+        "cbor"
+    }
+}
+
+impl TagDeserializer for CborSerializer {
+    fn deserialize(&self, data: &[u8]) -> Result<Vec<String>> {
+        Ok(ciborium::from_reader(data)?)
+    }
+
+    fn format_name(&self) -> &'static str {
+        "cbor"
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cbor_round_trip() {
+        let tags = vec!["tag1".to_string(), "tag,2".to_string(), "tag 3".to_string()];
+        let serializer = CborSerializer::new();
+
+        let bytes = serializer.serialize_bytes(&tags).unwrap();
+        assert_eq!(serializer.deserialize(&bytes).unwrap(), tags);
+    }
+
+    #[test]
+    fn test_cbor_smaller_than_json_for_repeated_tags() {
+        let tags: Vec<String> = (0..50).map(|i| format!("common-tag-{}", i % 5)).collect();
+
+        let json_bytes = JsonSerializer::new().serialize_bytes(&tags).unwrap();
+        let cbor_bytes = CborSerializer::new().serialize_bytes(&tags).unwrap();
+
+        assert!(cbor_bytes.len() < json_bytes.len());
+    }
+
+    #[test]
+    fn test_space_and_comma_round_trip() {
+        let tags = vec!["tag1".to_string(), "tag2".to_string(), "tag3".to_string()];
+
+        let space = SpaceSeparatedSerializer::new();
+        let space_bytes = space.serialize_bytes(&tags).unwrap();
+        assert_eq!(space.deserialize(&space_bytes).unwrap(), tags);
+
+        let comma = CommaSeparatedSerializer::new();
+        let comma_bytes = comma.serialize_bytes(&tags).unwrap();
+        assert_eq!(comma.deserialize(&comma_bytes).unwrap(), tags);
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let tags = vec!["tag1".to_string(), "tag2".to_string()];
+        let json = JsonSerializer::new();
+
+        let bytes = json.serialize_bytes(&tags).unwrap();
+        assert_eq!(json.deserialize(&bytes).unwrap(), tags);
+    }
+}
@@ -4,6 +4,7 @@
 //! - Huffman coding (frequency-based)
 //! - Arithmetic coding
 //! - Custom dictionary with variable-length codes
+//! - FSST (Fast Static Symbol Table) substring coding
 //!
 //! # Example
 //!
@@ -26,5 +27,12 @@ pub mod plotting;
 #[cfg(feature = "mappy-integration")]
 pub mod mappy_integration;
 
-pub use compression::{ArithmeticCompressor, Compressor, DictionaryCompressor, HuffmanCompressor};
-pub use formats::{CommaSeparatedParser, JsonParser, SpaceSeparatedParser, TagParser};
+pub use compression::{
+    ArithmeticCompressor, Compressor, DictionaryCompressor, DictionaryStreamingCompressor,
+    DictionaryStreamingDecompressor, FsstCompressor, HuffmanCompressor, StreamingCompressor,
+    StreamingDecompressor,
+};
+pub use formats::{
+    CaptionParser, CommaSeparatedParser, CsvParser, JsonParser, SpaceSeparatedParser, TagParser,
+    parser_for,
+};
@@ -1,12 +1,20 @@
 //! Integration with mappy for storing compressed tags
 
 use crate::compression::{
-    ArithmeticCompressor, Compressor, DictionaryCompressor, HuffmanCompressor,
+    ArithmeticCompressor, BloomFilter, Compressor, DictionaryCompressor, FsstCompressor, HuffmanCompressor,
 };
-use anyhow::Result;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+/// 20-byte SHA-1 content digest of a compressed tag set, used to key a content-addressed
+/// store so duplicate compressed payloads collapse to a single entry.
+pub type Digest = [u8; 20];
 
 /// Storage metrics for mappy integration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct MappyStorageMetrics {
     pub key: String,
     pub original_size: usize,
@@ -16,9 +24,47 @@ pub struct MappyStorageMetrics {
     pub total_storage_ratio: f64, // compressed_size / mappy_storage_size
 }
 
+/// Running totals backing `MappyTagStorage::metrics_snapshot`. Plain atomics rather than a
+/// field behind `&mut self` so `decompress_tags`/`decompress_batch`, which only need
+/// `&self`, can record usage too.
+#[derive(Default)]
+struct RunningMetrics {
+    total_original_bytes: AtomicU64,
+    total_compressed_bytes: AtomicU64,
+    operation_count: AtomicU64,
+    total_op_nanos: AtomicU64,
+}
+
+impl RunningMetrics {
+    fn record(&self, original_bytes: usize, compressed_bytes: usize, elapsed: std::time::Duration) {
+        self.total_original_bytes.fetch_add(original_bytes as u64, Ordering::Relaxed);
+        self.total_compressed_bytes.fetch_add(compressed_bytes as u64, Ordering::Relaxed);
+        self.operation_count.fetch_add(1, Ordering::Relaxed);
+        self.total_op_nanos.fetch_add(elapsed.as_nanos() as u64, Ordering::Relaxed);
+    }
+}
+
+/// A point-in-time read of a `MappyTagStorage`'s cumulative compression metrics across
+/// every `compress_tags`/`decompress_tags` call so far, the way a long-lived storage engine
+/// reports ratio-on-write over a whole workload rather than a single synthetic benchmark
+/// run.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct MetricsSnapshot {
+    pub total_original_bytes: u64,
+    pub total_compressed_bytes: u64,
+    pub operation_count: u64,
+    pub cumulative_ratio: f64,
+    pub avg_op_latency_secs: f64,
+}
+
 /// Helper for storing compressed tags in mappy
 pub struct MappyTagStorage {
     compressor: Box<dyn Compressor>,
+    metrics: RunningMetrics,
+    /// Per-blob Bloom filter keyed by content digest, populated by `compress_tags` so
+    /// `contains_tag` can answer membership for anything this instance compressed without
+    /// decompressing it again.
+    bloom_index: HashMap<Digest, BloomFilter>,
 }
 
 impl MappyTagStorage {
@@ -26,6 +72,8 @@ impl MappyTagStorage {
     pub fn with_huffman() -> Self {
         Self {
             compressor: Box::new(HuffmanCompressor::new()),
+            metrics: RunningMetrics::default(),
+            bloom_index: HashMap::new(),
         }
     }
 
@@ -33,6 +81,8 @@ impl MappyTagStorage {
     pub fn with_arithmetic() -> Self {
         Self {
             compressor: Box::new(ArithmeticCompressor::new()),
+            metrics: RunningMetrics::default(),
+            bloom_index: HashMap::new(),
         }
     }
 
@@ -40,6 +90,61 @@ impl MappyTagStorage {
     pub fn with_dictionary() -> Self {
         Self {
             compressor: Box::new(DictionaryCompressor::new()),
+            metrics: RunningMetrics::default(),
+            bloom_index: HashMap::new(),
+        }
+    }
+
+    /// Create with FSST compressor
+    pub fn with_fsst() -> Self {
+        Self {
+            compressor: Box::new(FsstCompressor::new()),
+            metrics: RunningMetrics::default(),
+            bloom_index: HashMap::new(),
+        }
+    }
+
+    /// Create with a DEFLATE/zlib compressor at the given speed/ratio tradeoff.
+    #[cfg(feature = "deflate-backend")]
+    pub fn with_deflate(mode: crate::compression::CompressionMode) -> Self {
+        Self {
+            compressor: Box::new(crate::compression::DeflateCompressor::new().with_mode(mode)),
+            metrics: RunningMetrics::default(),
+            bloom_index: HashMap::new(),
+        }
+    }
+
+    /// Sum of each tag's byte length plus one separator byte, the same "tags as a
+    /// space-joined byte stream" convention `BenchmarkRunner` uses to size `original_size`.
+    fn tag_bytes_len(tags: &[String]) -> usize {
+        tags.iter().map(|t| t.len() + 1).sum()
+    }
+
+    /// Cumulative compression metrics accumulated across every `compress_tags`/
+    /// `decompress_tags` call on this instance so far.
+    pub fn metrics_snapshot(&self) -> MetricsSnapshot {
+        let total_original_bytes = self.metrics.total_original_bytes.load(Ordering::Relaxed);
+        let total_compressed_bytes = self.metrics.total_compressed_bytes.load(Ordering::Relaxed);
+        let operation_count = self.metrics.operation_count.load(Ordering::Relaxed);
+        let total_op_nanos = self.metrics.total_op_nanos.load(Ordering::Relaxed);
+
+        let cumulative_ratio = if total_original_bytes == 0 {
+            0.0
+        } else {
+            total_compressed_bytes as f64 / total_original_bytes as f64
+        };
+        let avg_op_latency_secs = if operation_count == 0 {
+            0.0
+        } else {
+            (total_op_nanos as f64 / operation_count as f64) / 1_000_000_000.0
+        };
+
+        MetricsSnapshot {
+            total_original_bytes,
+            total_compressed_bytes,
+            operation_count,
+            cumulative_ratio,
+            avg_op_latency_secs,
         }
     }
 
@@ -61,14 +166,75 @@ impl MappyTagStorage {
                 dict.build_from_corpus(tags)?;
                 self.compressor = Box::new(dict);
             }
+            "fsst" => {
+                let mut fsst = FsstCompressor::new();
+                fsst.train_bulk(tags)?;
+                self.compressor = Box::new(fsst);
+            }
             _ => {}
         }
         Ok(())
     }
 
+    /// Train one shared code/symbol table over the union of many documents rather than a
+    /// single tag list, so the table's training cost is amortized across a whole corpus
+    /// instead of being paid again by every document. Each document is still compressed
+    /// independently afterwards (via `compress_tags`), against this frozen shared table.
+    pub fn train_bulk(&mut self, documents: &[&[String]]) -> Result<()> {
+        let union: Vec<String> = documents.iter().flat_map(|doc| doc.iter().cloned()).collect();
+        self.build_corpus(&union)
+    }
+
+    /// Build corpus from a tag stream rather than a fully materialized `Vec<String>`. Only
+    /// the Huffman backend actually streams (`HuffmanCompressor::build_corpus_streaming`
+    /// counts exact per-tag frequencies but never needs every occurrence in memory at
+    /// once, using a `HyperLogLog` estimator for `distinct_tags_estimate` on the side);
+    /// other backends have no streaming entry point, so the iterator is collected before
+    /// handing off to their existing `build_corpus` path.
+    pub fn build_corpus_streaming(&mut self, tags: impl Iterator<Item = String>) -> Result<()> {
+        if self.compressor.algorithm_name() == "huffman" {
+            let mut huffman = HuffmanCompressor::new();
+            huffman.build_corpus_streaming(tags)?;
+            self.compressor = Box::new(huffman);
+            Ok(())
+        } else {
+            self.build_corpus(&tags.collect::<Vec<_>>())
+        }
+    }
+
+    /// The HyperLogLog-estimated number of distinct tags seen by the most recent
+    /// `build_corpus_streaming` call, if the current backend supports it and was built that
+    /// way; `None` otherwise.
+    pub fn distinct_tags_estimate(&self) -> Option<f64> {
+        self.compressor.distinct_tags_estimate()
+    }
+
     /// Compress tags for mappy storage (corpus must be built first)
     pub fn compress_tags(&mut self, tags: &[String]) -> Result<Vec<u8>> {
-        self.compressor.compress(tags)
+        let start = Instant::now();
+        let compressed = self.compressor.compress(tags)?;
+        self.metrics.record(Self::tag_bytes_len(tags), compressed.len(), start.elapsed());
+
+        let mut filter = BloomFilter::new(tags.len(), 0.01);
+        for tag in tags {
+            filter.insert(tag);
+        }
+        self.bloom_index.insert(Self::digest(&compressed), filter);
+
+        Ok(compressed)
+    }
+
+    /// Check whether `tag` is present in a blob previously produced by `compress_tags`.
+    ///
+    /// If this instance built the Bloom filter for `compressed`'s digest (i.e. it was the one
+    /// that compressed it), the check is a handful of hash lookups against the filter with no
+    /// decompression at all. Otherwise falls back to the wrapped compressor's default
+    /// decompress-and-scan (`Compressor::contains_tag`).
+    pub fn contains_tag(&self, compressed: &[u8], tag: &str) -> Result<bool> {
+        match self.bloom_index.get(&Self::digest(compressed)) {
+            Some(filter) => Ok(filter.contains(tag)),
+            None => self.compressor.contains_tag(compressed, tag),
+        }
     }
 
     /// Compress tags and build corpus if needed (convenience method)
@@ -79,11 +245,230 @@ impl MappyTagStorage {
 
     /// Decompress tags from mappy storage
     pub fn decompress_tags(&self, data: &[u8]) -> Result<Vec<String>> {
-        self.compressor.decompress(data)
+        let start = Instant::now();
+        let tags = self.compressor.decompress(data)?;
+        self.metrics.record(Self::tag_bytes_len(&tags), data.len(), start.elapsed());
+        Ok(tags)
     }
 
     /// Get algorithm name
     pub fn algorithm_name(&self) -> &'static str {
         self.compressor.algorithm_name()
     }
+
+    /// Compute the content digest of already-compressed tag bytes, for keying a
+    /// content-addressed store so identical compressed payloads (e.g. duplicate tag sets)
+    /// collapse to a single stored entry.
+    pub fn digest(compressed: &[u8]) -> Digest {
+        use sha1::{Digest as _, Sha1};
+        let hash = Sha1::digest(compressed);
+        let mut digest = [0u8; 20];
+        digest.copy_from_slice(&hash);
+        digest
+    }
+
+    /// Decompress a batch of `(digest, compressed bytes)` entries in one pass, deduping
+    /// repeated digests first so a payload shared by many logical items is decompressed
+    /// only once, mirroring a bulk "embeddings_for_digests"-style fetch.
+    pub fn decompress_batch(
+        &self,
+        entries: &[(Digest, Vec<u8>)],
+    ) -> Result<HashMap<Digest, Vec<String>>> {
+        let mut unique_compressed: HashMap<Digest, &[u8]> = HashMap::new();
+        for (digest, compressed) in entries {
+            unique_compressed.entry(*digest).or_insert(compressed);
+        }
+
+        let mut out = HashMap::with_capacity(unique_compressed.len());
+        for (digest, compressed) in unique_compressed {
+            out.insert(digest, self.decompress_tags(compressed)?);
+        }
+        Ok(out)
+    }
+
+    /// Default number of hash functions in a MinHash signature; 128 keeps the estimated
+    /// Jaccard error small while staying cheap to compute. Callers that need a different
+    /// accuracy/speed tradeoff should call `minhash_signature_with_size` directly.
+    pub const DEFAULT_MINHASH_SIZE: usize = 128;
+
+    /// Compute a MinHash signature for a tag set, so similarity can be estimated directly
+    /// from signatures (via `approx_jaccard`) without reconstructing the original tags.
+    /// Uses `DEFAULT_MINHASH_SIZE` hash functions; see `minhash_signature_with_size` for a
+    /// configurable `n`.
+    pub fn minhash_signature(tags: &[String]) -> Vec<u64> {
+        Self::minhash_signature_with_size(tags, Self::DEFAULT_MINHASH_SIZE)
+    }
+
+    /// Compute a MinHash signature using `n` independent hash functions
+    /// `h_i(x) = (a_i * h(x) + b_i) mod p`, keeping the minimum value per function across
+    /// every tag in the set. An empty tag set gets an all-`u64::MAX` signature; see
+    /// `approx_jaccard` for how that's treated when comparing signatures.
+    pub fn minhash_signature_with_size(tags: &[String], n: usize) -> Vec<u64> {
+        if tags.is_empty() {
+            return vec![u64::MAX; n];
+        }
+
+        let tag_hashes: Vec<u64> = tags.iter().map(|tag| minhash::hash_tag(tag)).collect();
+        minhash::coefficients(n)
+            .iter()
+            .map(|&(a, b)| {
+                tag_hashes
+                    .iter()
+                    .map(|&h| minhash::hash_function(a, b, h))
+                    .min()
+                    .unwrap_or(u64::MAX)
+            })
+            .collect()
+    }
+}
+
+/// Groups many tag sets into fixed-size blocks and compresses each block as one unit (every
+/// tag from every item in the block, flattened), so `fetch_items` can decompress a block
+/// exactly once no matter how many of its items a caller requests, instead of paying one
+/// decompression per item. Item boundaries within a block's flat decompressed tag list are
+/// tracked separately via `item_tag_counts`, since `Compressor` only round-trips a flat
+/// `Vec<String>` and has no notion of sub-lists.
+pub struct TagBlockStore {
+    compressor: Box<dyn Compressor>,
+    block_size: usize,
+    /// One compressed blob per block.
+    block_bytes: Vec<Vec<u8>>,
+    /// Per-item tag count, in insertion order, so a block's flat decompressed tag list can
+    /// be split back into the individual tag sets it holds.
+    item_tag_counts: Vec<usize>,
+}
+
+impl TagBlockStore {
+    /// Pack `tag_sets` into blocks of `block_size` items apiece (the last block may be
+    /// smaller) and compress each block with `compressor`.
+    pub fn build(
+        tag_sets: &[Vec<String>],
+        compressor: Box<dyn Compressor>,
+        block_size: usize,
+    ) -> Result<Self> {
+        let block_size = block_size.max(1);
+        let mut block_bytes = Vec::with_capacity(tag_sets.len().div_ceil(block_size));
+        let mut item_tag_counts = Vec::with_capacity(tag_sets.len());
+
+        for block in tag_sets.chunks(block_size) {
+            let flattened: Vec<String> = block.iter().flatten().cloned().collect();
+            block_bytes.push(compressor.compress(&flattened)?);
+            item_tag_counts.extend(block.iter().map(Vec::len));
+        }
+
+        Ok(Self {
+            compressor,
+            block_size,
+            block_bytes,
+            item_tag_counts,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.item_tag_counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.item_tag_counts.is_empty()
+    }
+
+    fn block_of(&self, item_idx: usize) -> usize {
+        item_idx / self.block_size
+    }
+
+    /// Fetch the tag sets at `indices`, decompressing each underlying block at most once
+    /// regardless of how many requested indices fall in it. Within a block, requested
+    /// ordinals are sorted first and then consumed in a single forward walk over the flat
+    /// decompressed tag list (rather than recomputing each item's offset independently), so
+    /// the scan advances monotonically. Returns items in the same order as `indices`.
+    pub fn fetch_items(&self, indices: &[usize]) -> Result<Vec<Vec<String>>> {
+        let mut by_block: HashMap<usize, Vec<usize>> = HashMap::new();
+        for &idx in indices {
+            by_block.entry(self.block_of(idx)).or_default().push(idx);
+        }
+
+        let mut resolved: HashMap<usize, Vec<String>> = HashMap::with_capacity(indices.len());
+        for (block_idx, mut ordinals) in by_block {
+            ordinals.sort_unstable();
+            ordinals.dedup();
+            let flat = self.compressor.decompress(&self.block_bytes[block_idx])?;
+
+            let block_start = block_idx * self.block_size;
+            let block_end = self.item_tag_counts.len().min(block_start + self.block_size);
+
+            let mut offset = 0usize;
+            let mut wanted = ordinals.into_iter().peekable();
+            for item_idx in block_start..block_end {
+                let count = self.item_tag_counts[item_idx];
+                if wanted.peek() == Some(&item_idx) {
+                    resolved.insert(item_idx, flat[offset..offset + count].to_vec());
+                    wanted.next();
+                }
+                offset += count;
+            }
+        }
+
+        indices
+            .iter()
+            .map(|idx| resolved.get(idx).cloned().context("tag-block store index out of range"))
+            .collect()
+    }
+}
+
+/// Estimate the Jaccard similarity of two tag sets from their MinHash signatures: the
+/// fraction of positions where the two sets' per-function minima agree. Signatures of
+/// different lengths (or either one built from an empty tag set) are defined as
+/// dissimilar (0.0) rather than compared position-by-position, since an empty set's
+/// Jaccard similarity to anything is itself undefined rather than a match.
+pub fn approx_jaccard(sig_a: &[u64], sig_b: &[u64]) -> f64 {
+    if sig_a.is_empty() || sig_a.len() != sig_b.len() {
+        return 0.0;
+    }
+    if sig_a.iter().all(|&v| v == u64::MAX) || sig_b.iter().all(|&v| v == u64::MAX) {
+        return 0.0;
+    }
+
+    let agreeing = sig_a.iter().zip(sig_b.iter()).filter(|(a, b)| a == b).count();
+    agreeing as f64 / sig_a.len() as f64
+}
+
+/// MinHash hash-function family helpers. Kept private: `MappyTagStorage` and
+/// `approx_jaccard` are the public surface, this is just their shared plumbing.
+mod minhash {
+    use std::hash::{Hash, Hasher};
+
+    /// A large Mersenne prime (2^61 - 1), standard for MinHash's `(a*h+b) mod p` family.
+    const PRIME: u64 = (1u64 << 61) - 1;
+
+    pub(super) fn hash_tag(tag: &str) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        tag.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    pub(super) fn hash_function(a: u64, b: u64, x: u64) -> u64 {
+        ((a as u128 * x as u128 + b as u128) % PRIME as u128) as u64
+    }
+
+    /// Deterministic (not OS-random) `(a, b)` coefficients for `n` independent hash
+    /// functions, seeded with a fixed constant via `SplitMix64` so two calls with the same
+    /// `n` always produce comparable signatures.
+    pub(super) fn coefficients(n: usize) -> Vec<(u64, u64)> {
+        fn splitmix64(state: &mut u64) -> u64 {
+            *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = *state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        }
+
+        let mut state = 0x5EED_u64;
+        (0..n)
+            .map(|_| {
+                let a = (splitmix64(&mut state) % PRIME).max(1);
+                let b = splitmix64(&mut state) % PRIME;
+                (a, b)
+            })
+            .collect()
+    }
 }
@@ -0,0 +1,67 @@
+//! Braille-cell bar rendering for terminal output, with no image/plotting dependency.
+//!
+//! Renders a horizontal bar using Unicode braille dot patterns (U+2800 base, a 2-column by
+//! 4-row dot grid per cell) so comparison reports stay legible in headless CI logs or over
+//! SSH, where `BitMapBackend`'s PNG output can't be viewed at all. Each cell's left and
+//! right dot columns are filled independently, giving twice the horizontal resolution of a
+//! plain block-character bar for the same terminal width.
+
+/// Render a horizontal bar `width_cells` braille cells wide, filled to `value / max_value`
+/// of its length (clamped to `[0.0, 1.0]`; `max_value <= 0.0` renders an empty bar).
+pub fn braille_bar(value: f64, max_value: f64, width_cells: usize) -> String {
+    if max_value <= 0.0 || width_cells == 0 {
+        return String::new();
+    }
+
+    let fraction = (value / max_value).clamp(0.0, 1.0);
+    let total_columns = width_cells * 2;
+    let filled_columns = (fraction * total_columns as f64).round() as usize;
+
+    (0..width_cells)
+        .map(|cell| braille_cell(filled_columns > cell * 2, filled_columns > cell * 2 + 1))
+        .collect()
+}
+
+/// A single braille cell with its left and/or right dot column fully filled (all 4 rows),
+/// used as one horizontal-resolution step of a `braille_bar`.
+fn braille_cell(left_filled: bool, right_filled: bool) -> char {
+    const BASE: u32 = 0x2800;
+    // Unicode braille dot-bit layout: dots 1/2/3/7 form the left column, dots 4/5/6/8 the
+    // right column (dot N is bit N-1).
+    const LEFT_COLUMN: u32 = 0b0100_0111;
+    const RIGHT_COLUMN: u32 = 0b1011_1000;
+
+    let mut bits = 0u32;
+    if left_filled {
+        bits |= LEFT_COLUMN;
+    }
+    if right_filled {
+        bits |= RIGHT_COLUMN;
+    }
+    char::from_u32(BASE + bits).unwrap_or(' ')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_value_renders_blank_cells() {
+        assert_eq!(braille_bar(0.0, 100.0, 4), "\u{2800}\u{2800}\u{2800}\u{2800}");
+    }
+
+    #[test]
+    fn test_full_value_renders_full_cells() {
+        assert_eq!(braille_bar(100.0, 100.0, 4), "\u{28ff}\u{28ff}\u{28ff}\u{28ff}");
+    }
+
+    #[test]
+    fn test_half_value_fills_half_the_columns() {
+        assert_eq!(braille_bar(50.0, 100.0, 4), "\u{28ff}\u{28ff}\u{2800}\u{2800}");
+    }
+
+    #[test]
+    fn test_non_positive_max_value_renders_empty_string() {
+        assert_eq!(braille_bar(5.0, 0.0, 4), "");
+    }
+}
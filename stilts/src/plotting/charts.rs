@@ -3,6 +3,8 @@
 use anyhow::Result;
 use plotters::prelude::*;
 use crate::benchmark::metrics::BenchmarkMetrics;
+use std::fs::File;
+use std::io::Write;
 
 /// Chart generator
 pub struct ChartGenerator;
@@ -133,5 +135,135 @@ impl ChartGenerator {
         root.present()?;
         Ok(())
     }
+
+    /// Write the three comparison charts into `dir` plus an HTML dashboard embedding them
+    /// alongside a client-side-sortable table of every `metrics` row (click a header to
+    /// sort by that column), so a benchmark run becomes one shareable artifact instead of
+    /// three standalone PNGs. Returns the path to the written HTML file.
+    pub fn report_html(metrics: &[BenchmarkMetrics], dir: &str) -> Result<String> {
+        let dir = std::path::Path::new(dir);
+        std::fs::create_dir_all(dir)?;
+
+        let ratio_chart = dir.join("compression_ratio.png");
+        let speed_chart = dir.join("speed_comparison.png");
+        let scatter_chart = dir.join("ratio_vs_speed.png");
+
+        Self::compression_ratio_chart(metrics, ratio_chart.to_str().unwrap())?;
+        Self::speed_comparison_chart(metrics, speed_chart.to_str().unwrap())?;
+        Self::ratio_vs_speed_chart(metrics, scatter_chart.to_str().unwrap())?;
+
+        let mut html = String::from(
+            r#"<!DOCTYPE html>
+<html>
+<head>
+    <title>Stilts Compression Benchmark Dashboard</title>
+    <style>
+        body { font-family: Arial, sans-serif; margin: 20px; }
+        h1 { color: #333; }
+        table { border-collapse: collapse; width: 100%; margin: 20px 0; }
+        th, td { border: 1px solid #ddd; padding: 8px; text-align: left; }
+        th { background-color: #4CAF50; color: white; cursor: pointer; user-select: none; }
+        img { max-width: 100%; height: auto; margin: 20px 0; }
+    </style>
+    <script>
+        function sortTable(colIndex) {
+            const table = document.getElementById("metrics-table");
+            const rows = Array.from(table.tBodies[0].rows);
+            const numeric = rows.every(row => !isNaN(parseFloat(row.cells[colIndex].textContent)));
+            const dir = table.dataset.sortCol == colIndex && table.dataset.sortDir == "asc" ? "desc" : "asc";
+            rows.sort((a, b) => {
+                const av = a.cells[colIndex].textContent, bv = b.cells[colIndex].textContent;
+                const cmp = numeric ? parseFloat(av) - parseFloat(bv) : av.localeCompare(bv);
+                return dir == "asc" ? cmp : -cmp;
+            });
+            rows.forEach(row => table.tBodies[0].appendChild(row));
+            table.dataset.sortCol = colIndex;
+            table.dataset.sortDir = dir;
+        }
+    </script>
+</head>
+<body>
+    <h1>Stilts Compression Benchmark Dashboard</h1>
+    <h2>Results Summary (click a header to sort)</h2>
+    <table id="metrics-table" data-sort-col="" data-sort-dir="">
+        <thead>
+            <tr>
+                <th onclick="sortTable(0)">Algorithm</th>
+                <th onclick="sortTable(1)">Compression Ratio</th>
+                <th onclick="sortTable(2)">Compression Speed (MB/s)</th>
+                <th onclick="sortTable(3)">Decompression Speed (MB/s)</th>
+                <th onclick="sortTable(4)">Compressed Size (bytes)</th>
+            </tr>
+        </thead>
+        <tbody>
+"#,
+        );
+
+        for metric in metrics {
+            html.push_str(&format!(
+                r#"            <tr>
+                <td>{}</td>
+                <td>{:.4}</td>
+                <td>{:.2}</td>
+                <td>{:.2}</td>
+                <td>{}</td>
+            </tr>
+"#,
+                metric.algorithm,
+                metric.stats.compression_ratio,
+                metric.stats.compression_speed_mbps,
+                metric.stats.decompression_speed_mbps,
+                metric.stats.compressed_size,
+            ));
+        }
+
+        html.push_str(
+            r#"        </tbody>
+    </table>
+    <h2>Charts</h2>
+    <h3>Compression Ratio Comparison</h3>
+    <img src="compression_ratio.png" alt="Compression Ratio">
+    <h3>Speed Comparison</h3>
+    <img src="speed_comparison.png" alt="Speed Comparison">
+    <h3>Compression Ratio vs Speed</h3>
+    <img src="ratio_vs_speed.png" alt="Ratio vs Speed">
+</body>
+</html>"#,
+        );
+
+        let html_path = dir.join("dashboard.html");
+        let mut file = File::create(&html_path)?;
+        file.write_all(html.as_bytes())?;
+
+        Ok(html_path.to_string_lossy().into_owned())
+    }
+
+    /// Serialize each algorithm's `CompressionStats` (ratio, throughput, sizes, memory) as
+    /// one CSV row, so benchmark results can be diffed across commits without re-parsing
+    /// chart images.
+    pub fn export_csv(metrics: &[BenchmarkMetrics], path: &str) -> Result<String> {
+        let mut csv = String::from(
+            "algorithm,compression_ratio,compression_speed_mbps,decompression_speed_mbps,original_size,compressed_size,dictionary_size,memory_usage_bytes\n",
+        );
+
+        for metric in metrics {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{}\n",
+                metric.algorithm,
+                metric.stats.compression_ratio,
+                metric.stats.compression_speed_mbps,
+                metric.stats.decompression_speed_mbps,
+                metric.stats.original_size,
+                metric.stats.compressed_size,
+                metric.stats.dictionary_size,
+                metric.memory_usage_bytes,
+            ));
+        }
+
+        let mut file = File::create(path)?;
+        file.write_all(csv.as_bytes())?;
+
+        Ok(path.to_string())
+    }
 }
 
@@ -1,6 +1,7 @@
 #![allow(clippy::cast_precision_loss)] // Acceptable for plotting calculations
 //! Plotting and visualization utilities
 
+pub mod braille;
 pub mod charts;
 pub mod reports;
 
@@ -10,5 +11,6 @@ pub mod storage_charts;
 #[cfg(feature = "mappy-integration")]
 pub mod ml_reports;
 
+pub use braille::braille_bar;
 pub use charts::ChartGenerator;
 pub use reports::ReportGenerator;
@@ -2,6 +2,7 @@
 //! Report generation
 
 use crate::benchmark::metrics::BenchmarkMetrics;
+use crate::plotting::braille::braille_bar;
 use crate::plotting::charts::ChartGenerator;
 use anyhow::Result;
 use serde_json;
@@ -61,11 +62,32 @@ impl ReportGenerator {
             <th>Compression Speed (MB/s)</th>
             <th>Decompression Speed (MB/s)</th>
             <th>Compressed Size (bytes)</th>
+            <th>Complexity</th>
+            <th>Compression Time 95% CI (ms)</th>
+            <th>Outliers</th>
         </tr>
 "#,
         );
 
         for metric in metrics {
+            let complexity = metric
+                .complexity
+                .as_ref()
+                .map(|c| format!("{} (c={:.4}, rms={:.4})", c.model, c.coefficient, c.rms))
+                .unwrap_or_default();
+
+            let (confidence_interval, outlier_count) = metric
+                .stats
+                .compression_time_stats
+                .as_ref()
+                .map(|s| {
+                    (
+                        format!("[{:.4}, {:.4}]", s.confidence_interval_95.0, s.confidence_interval_95.1),
+                        s.outliers.len().to_string(),
+                    )
+                })
+                .unwrap_or_default();
+
             html.push_str(&format!(
                 r#"        <tr>
             <td>{}</td>
@@ -73,6 +95,9 @@ impl ReportGenerator {
             <td>{:.2}</td>
             <td>{:.2}</td>
             <td>{}</td>
+            <td>{}</td>
+            <td>{}</td>
+            <td>{}</td>
         </tr>
 "#,
                 metric.algorithm,
@@ -80,6 +105,9 @@ impl ReportGenerator {
                 metric.stats.compression_speed_mbps,
                 metric.stats.decompression_speed_mbps,
                 metric.stats.compressed_size,
+                complexity,
+                confidence_interval,
+                outlier_count,
             ));
         }
 
@@ -111,6 +139,153 @@ impl ReportGenerator {
         Ok(output_path.to_string())
     }
 
+    /// Is `algorithm` one of mappy's own compressors rather than an external codec?
+    fn is_mappy_own(algorithm: &str) -> bool {
+        matches!(algorithm, "dictionary" | "huffman" | "arithmetic" | "fsst")
+            || algorithm.starts_with("mappy-")
+    }
+
+    /// Generate a report focused on compression factor and throughput across every
+    /// `ComparisonRunner` backend, highlighting mappy's own compressors against the
+    /// external codecs (zlib/gzip/lz4/zstd/snappy/brotli) they're competing with.
+    pub fn generate_comparison_report(
+        metrics: &[BenchmarkMetrics],
+        output_path: &str,
+    ) -> Result<String> {
+        let reports_dir = std::path::Path::new("reports");
+        std::fs::create_dir_all(reports_dir)?;
+
+        let chart_dir = std::path::Path::new(output_path)
+            .parent()
+            .unwrap_or(reports_dir);
+
+        let ratio_chart = chart_dir.join("comparison_ratio.png");
+        let speed_chart = chart_dir.join("comparison_speed.png");
+
+        ChartGenerator::compression_ratio_chart(metrics, ratio_chart.to_str().unwrap())?;
+        ChartGenerator::speed_comparison_chart(metrics, speed_chart.to_str().unwrap())?;
+
+        let mut html = String::from(
+            r#"<!DOCTYPE html>
+<html>
+<head>
+    <title>Stilts Compression Comparison Report</title>
+    <style>
+        body { font-family: Arial, sans-serif; margin: 20px; }
+        h1 { color: #333; }
+        table { border-collapse: collapse; width: 100%; margin: 20px 0; }
+        th, td { border: 1px solid #ddd; padding: 8px; text-align: left; }
+        th { background-color: #4CAF50; color: white; }
+        tr.mappy-own { background-color: #e8f5e9; font-weight: bold; }
+        img { max-width: 100%; height: auto; margin: 20px 0; }
+    </style>
+</head>
+<body>
+    <h1>Stilts Compression Comparison Report</h1>
+    <p>Rows highlighted in green are mappy's own compressors.</p>
+    <h2>Results Summary</h2>
+    <table>
+        <tr>
+            <th>Algorithm</th>
+            <th>Compression Factor (original/compressed)</th>
+            <th>Compression Speed (MB/s)</th>
+            <th>Decompression Speed (MB/s)</th>
+            <th>Compressed Size (bytes)</th>
+        </tr>
+"#,
+        );
+
+        for metric in metrics {
+            let row_class = if Self::is_mappy_own(&metric.algorithm) {
+                "mappy-own"
+            } else {
+                ""
+            };
+            let factor = if metric.stats.compression_ratio > 0.0 {
+                1.0 / metric.stats.compression_ratio
+            } else {
+                0.0
+            };
+
+            html.push_str(&format!(
+                r#"        <tr class="{}">
+            <td>{}</td>
+            <td>{:.2}x</td>
+            <td>{:.2}</td>
+            <td>{:.2}</td>
+            <td>{}</td>
+        </tr>
+"#,
+                row_class,
+                metric.algorithm,
+                factor,
+                metric.stats.compression_speed_mbps,
+                metric.stats.decompression_speed_mbps,
+                metric.stats.compressed_size,
+            ));
+        }
+
+        html.push_str(
+            r#"    </table>
+    <h2>Charts</h2>
+    <h3>Compression Ratio Comparison</h3>
+    <img src="comparison_ratio.png" alt="Compression Ratio">
+    <h3>Throughput Comparison</h3>
+    <img src="comparison_speed.png" alt="Speed Comparison">
+</body>
+</html>"#,
+        );
+
+        let mut file = File::create(output_path)?;
+        file.write_all(html.as_bytes())?;
+
+        Ok(output_path.to_string())
+    }
+
+    /// Terminal-friendly width (in braille cells) for a comparison bar; four cells per
+    /// character beyond this just wastes horizontal space in a typical 80-column terminal.
+    const TERMINAL_BAR_WIDTH: usize = 24;
+
+    /// Render the same compression-ratio and speed comparisons as `generate_html_report`,
+    /// but as a compact table plus Unicode braille bar charts written directly to a string
+    /// for stdout/SSH/CI logs, with no PNG files and no `plotters` image dependency.
+    pub fn generate_terminal_report(metrics: &[BenchmarkMetrics]) -> String {
+        let max_ratio = metrics
+            .iter()
+            .map(|m| m.stats.compression_ratio)
+            .fold(0.0, f64::max);
+        let max_speed = metrics
+            .iter()
+            .flat_map(|m| [m.stats.compression_speed_mbps, m.stats.decompression_speed_mbps])
+            .fold(0.0, f64::max);
+
+        let mut out = String::from("Stilts Compression Benchmark Report\n\n");
+        out.push_str(&format!(
+            "{:<18} {:>10} {:>12} {:>14}\n",
+            "Algorithm", "Ratio", "Comp MB/s", "Decomp MB/s"
+        ));
+        out.push_str(&"-".repeat(58));
+        out.push('\n');
+
+        for metric in metrics {
+            out.push_str(&format!(
+                "{:<18} {:>10.4} {:>12.2} {:>14.2}\n",
+                metric.algorithm,
+                metric.stats.compression_ratio,
+                metric.stats.compression_speed_mbps,
+                metric.stats.decompression_speed_mbps,
+            ));
+            out.push_str(&format!(
+                "  ratio  {}\n  comp   {}\n  decomp {}\n",
+                braille_bar(metric.stats.compression_ratio, max_ratio, Self::TERMINAL_BAR_WIDTH),
+                braille_bar(metric.stats.compression_speed_mbps, max_speed, Self::TERMINAL_BAR_WIDTH),
+                braille_bar(metric.stats.decompression_speed_mbps, max_speed, Self::TERMINAL_BAR_WIDTH),
+            ));
+        }
+
+        out
+    }
+
     /// Generate comprehensive storage comparison report
     #[cfg(feature = "mappy-integration")]
     pub fn generate_storage_report(
@@ -207,4 +382,41 @@ impl ReportGenerator {
 
         Ok(output_path.to_string())
     }
+
+    /// Terminal-friendly rendering of `generate_storage_report`'s storage-size and
+    /// compression-ratio comparisons as braille bar charts, written directly to a string.
+    #[cfg(feature = "mappy-integration")]
+    pub fn generate_storage_terminal_report(comparisons: &[StorageComparison]) -> String {
+        let max_size = comparisons
+            .iter()
+            .map(|c| c.storage_size.max(c.original_size))
+            .fold(0, usize::max) as f64;
+        let max_ratio = comparisons
+            .iter()
+            .map(|c| c.compression_ratio)
+            .fold(0.0, f64::max);
+
+        let mut out = String::from("Stilts Storage Comparison Report\n\n");
+        out.push_str(&format!(
+            "{:<18} {:>14} {:>14} {:>10}\n",
+            "Method", "Original", "Storage", "Ratio"
+        ));
+        out.push_str(&"-".repeat(58));
+        out.push('\n');
+
+        for comp in comparisons {
+            out.push_str(&format!(
+                "{:<18} {:>14} {:>14} {:>10.4}\n",
+                comp.method, comp.original_size, comp.storage_size, comp.compression_ratio,
+            ));
+            out.push_str(&format!(
+                "  original {}\n  storage  {}\n  ratio    {}\n",
+                braille_bar(comp.original_size as f64, max_size, Self::TERMINAL_BAR_WIDTH),
+                braille_bar(comp.storage_size as f64, max_size, Self::TERMINAL_BAR_WIDTH),
+                braille_bar(comp.compression_ratio, max_ratio, Self::TERMINAL_BAR_WIDTH),
+            ));
+        }
+
+        out
+    }
 }